@@ -0,0 +1,74 @@
+//! Compares lock contention of `find_or_add_str` when (almost) every call is a hit.
+//!
+//! Run with `cargo bench --bench contention`.
+
+use std::{
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+use interner::{
+    sync::{DataInterner, ShardedDataInterner},
+    unsync::DataInterner as UnSyncDataInterner,
+    LookupMode,
+};
+
+const TOKENS: usize = 10_000;
+const OPS_PER_THREAD: usize = 200_000;
+
+fn run(threads: usize, find_or_add: impl Fn(&str) + Sync) -> Duration {
+    let tokens: Vec<String> = (0..TOKENS).map(|i| format!("token{i}")).collect();
+    for token in &tokens {
+        find_or_add(token);
+    }
+    let start = Instant::now();
+    thread::scope(|s| {
+        for t in 0..threads {
+            let (tokens, find_or_add) = (&tokens, &find_or_add);
+            s.spawn(move || {
+                for i in 0..OPS_PER_THREAD {
+                    find_or_add(&tokens[(i * 7919 + t) % TOKENS]);
+                }
+            });
+        }
+    });
+    start.elapsed()
+}
+
+fn report(name: &str, threads: usize, elapsed: Duration) {
+    let ops = (threads * OPS_PER_THREAD) as f64;
+    println!(
+        "{name:<40} {threads:>2} threads: {:>8.1} ns/op, {:>6.2} Mops/s",
+        elapsed.as_nanos() as f64 / ops,
+        ops / elapsed.as_secs_f64() / 1e6,
+    );
+}
+
+fn main() {
+    let max_threads = thread::available_parallelism().map_or(4, |n| n.get());
+    let mut thread_counts = vec![1, 2, 4, 8, 16, 32];
+    thread_counts.retain(|&n| n <= max_threads.max(4));
+
+    for &threads in &thread_counts {
+        // Every call takes an exclusive lock, like `sync::DataInterner::find_or_add_str` used to.
+        let interner = Mutex::new(UnSyncDataInterner::with_lookup_mode(LookupMode::ExactEntry));
+        let elapsed = run(threads, |value| {
+            interner.lock().unwrap().find_or_add_str(value);
+        });
+        report("exclusive lock (Mutex<unsync>)", threads, elapsed);
+
+        let interner = DataInterner::with_lookup_mode(LookupMode::ExactEntry);
+        let elapsed = run(threads, |value| {
+            interner.find_or_add_str(value);
+        });
+        report("shared lock on hit (sync)", threads, elapsed);
+
+        let interner = ShardedDataInterner::with_lookup_mode(4 * threads, LookupMode::ExactEntry);
+        let elapsed = run(threads, |value| {
+            interner.find_or_add_str(value);
+        });
+        report("sharded (sync::ShardedDataInterner)", threads, elapsed);
+        println!();
+    }
+}
@@ -0,0 +1,371 @@
+use std::{fmt, io, ops};
+
+use crate::Interner;
+
+/// A byte buffer that can be written to in place and then added to an interner.
+///
+/// Finalizing with [`finalize`](Self::finalize) passes the buffer to [`Interner::add_owned_bytes`],
+/// so its allocation (including any spare capacity) is reused by the interner instead of being copied.
+///
+/// # Example
+/// ```rust
+/// use std::io::Write;
+/// use interner::{builder::BytesBuilder, unsync::DataInterner};
+/// let interner = DataInterner::new();
+/// let mut builder = BytesBuilder::new(&interner);
+/// builder.extend_from_slice(b"Hello, ");
+/// write!(builder, "{}!", "Ferris").unwrap();
+/// let greeting: &[u8] = builder.finalize();
+/// assert_eq!(greeting, b"Hello, Ferris!");
+/// ```
+pub struct BytesBuilder<'a, I: Interner> {
+    data: Vec<u8>,
+    interner: &'a I,
+}
+
+impl<'a, I: Interner> BytesBuilder<'a, I> {
+    /// Creates a new, empty builder for `interner`. Does not allocate.
+    pub fn new(interner: &'a I) -> Self {
+        Self::with_capacity(interner, 0)
+    }
+
+    /// Creates a new, empty builder for `interner` with space for at least `capacity` bytes.
+    pub fn with_capacity(interner: &'a I, capacity: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+            interner,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.data.reserve(additional);
+    }
+
+    pub fn push(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    pub fn extend_from_slice(&mut self, slice: &[u8]) {
+        self.data.extend_from_slice(slice);
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        self.data.truncate(len);
+    }
+
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Adds the built data to the interner, returning a reference to it.
+    ///
+    /// The data is always added, even if equal data already exists in the interner.
+    pub fn finalize(self) -> &'a [u8] {
+        self.interner.add_owned_bytes(self.data)
+    }
+
+    /// Returns a reference to data in the interner equal to the built data, adding it with
+    /// [`finalize`](Self::finalize) only if it does not yet exist.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, builder::BytesBuilder, unsync::DataInterner};
+    /// let interner = DataInterner::new();
+    /// let existing = interner.add_bytes(b"Hello, world!");
+    /// let mut builder = BytesBuilder::new(&interner);
+    /// builder.extend_from_slice(b"Hello");
+    /// assert!(std::ptr::eq(builder.finalize_dedup(), &existing[..5]));
+    /// ```
+    pub fn finalize_dedup(self) -> &'a [u8] {
+        let interner = self.interner;
+        match interner.find_bytes(&self.data) {
+            Some(existing) => existing,
+            None => self.finalize(),
+        }
+    }
+}
+
+impl<'a, I: Interner> ops::Deref for BytesBuilder<'a, I> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl<'a, I: Interner> ops::DerefMut for BytesBuilder<'a, I> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.data
+    }
+}
+
+impl<'a, I: Interner> Extend<u8> for BytesBuilder<'a, I> {
+    fn extend<It: IntoIterator<Item = u8>>(&mut self, iter: It) {
+        self.data.extend(iter);
+    }
+}
+
+impl<'a, 'b, I: Interner> Extend<&'b u8> for BytesBuilder<'a, I> {
+    fn extend<It: IntoIterator<Item = &'b u8>>(&mut self, iter: It) {
+        self.data.extend(iter);
+    }
+}
+
+impl<'a, I: Interner> io::Write for BytesBuilder<'a, I> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.data.extend_from_slice(buf);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a, I: Interner> fmt::Write for BytesBuilder<'a, I> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.data.extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+}
+
+impl<'a, I: Interner> fmt::Debug for BytesBuilder<'a, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BytesBuilder").field(&self.data).finish()
+    }
+}
+
+/// A string buffer that can be written to in place and then added to an interner.
+///
+/// This is the string equivalent of [`BytesBuilder`]: finalizing reuses the builder's allocation
+/// instead of building a `String` and copying it into the interner.
+///
+/// # Example
+/// ```rust
+/// use std::fmt::Write;
+/// use interner::{builder::StrBuilder, sync::DataInterner};
+/// let interner = DataInterner::new();
+/// let mut builder = StrBuilder::new(&interner);
+/// for i in 0..3 {
+///     write!(builder, "let x{i} = {i};").unwrap();
+/// }
+/// builder.push('\n');
+/// let code: &str = builder.finalize();
+/// assert_eq!(code, "let x0 = 0;let x1 = 1;let x2 = 2;\n");
+/// ```
+pub struct StrBuilder<'a, I: Interner> {
+    // Invariant: bytes is always valid UTF-8
+    bytes: BytesBuilder<'a, I>,
+}
+
+impl<'a, I: Interner> StrBuilder<'a, I> {
+    /// Creates a new, empty builder for `interner`. Does not allocate.
+    pub fn new(interner: &'a I) -> Self {
+        Self {
+            bytes: BytesBuilder::new(interner),
+        }
+    }
+
+    /// Creates a new, empty builder for `interner` with space for at least `capacity` bytes.
+    pub fn with_capacity(interner: &'a I, capacity: usize) -> Self {
+        Self {
+            bytes: BytesBuilder::with_capacity(interner, capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.bytes.capacity()
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.bytes.reserve(additional);
+    }
+
+    pub fn push(&mut self, ch: char) {
+        self.bytes
+            .extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes());
+    }
+
+    pub fn push_str(&mut self, s: &str) {
+        self.bytes.extend_from_slice(s.as_bytes());
+    }
+
+    /// Shortens this builder to `new_len` bytes.
+    ///
+    /// Has no effect if `new_len` is greater than the builder's current length.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` does not lie on a `char` boundary.
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len <= self.len() {
+            assert!(self.as_str().is_char_boundary(new_len));
+            self.bytes.truncate(new_len);
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<char> {
+        let ch = self.as_str().chars().next_back()?;
+        self.bytes.truncate(self.len() - ch.len_utf8());
+        Some(ch)
+    }
+
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+    }
+
+    pub fn as_str(&self) -> &str {
+        // SAFETY: bytes is always valid UTF-8
+        unsafe { std::str::from_utf8_unchecked(&self.bytes) }
+    }
+
+    pub fn as_mut_str(&mut self) -> &mut str {
+        // SAFETY: bytes is always valid UTF-8
+        unsafe { std::str::from_utf8_unchecked_mut(&mut self.bytes) }
+    }
+
+    /// Adds the built string to the interner, returning a reference to it.
+    ///
+    /// The string is always added, even if an equal string already exists in the interner.
+    pub fn finalize(self) -> &'a str {
+        let owned = self.bytes.finalize();
+        // SAFETY: owned == self.bytes bytewise, and self.bytes is valid UTF-8
+        unsafe { std::str::from_utf8_unchecked(owned) }
+    }
+
+    /// Returns a reference to a string in the interner equal to the built string, adding it with
+    /// [`finalize`](Self::finalize) only if it does not yet exist.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, builder::StrBuilder, unsync::DataInterner};
+    /// let interner = DataInterner::new();
+    /// let existing = interner.add_str("Hello, world!");
+    /// let mut builder = StrBuilder::new(&interner);
+    /// builder.push_str("Hello, world!");
+    /// assert!(std::ptr::eq(builder.finalize_dedup(), existing));
+    /// ```
+    pub fn finalize_dedup(self) -> &'a str {
+        let owned = self.bytes.finalize_dedup();
+        // SAFETY: owned == self.bytes bytewise, and self.bytes is valid UTF-8
+        unsafe { std::str::from_utf8_unchecked(owned) }
+    }
+}
+
+impl<'a, I: Interner> ops::Deref for StrBuilder<'a, I> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl<'a, I: Interner> ops::DerefMut for StrBuilder<'a, I> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut_str()
+    }
+}
+
+impl<'a, I: Interner> Extend<char> for StrBuilder<'a, I> {
+    fn extend<It: IntoIterator<Item = char>>(&mut self, iter: It) {
+        iter.into_iter().for_each(|ch| self.push(ch));
+    }
+}
+
+impl<'a, 'b, I: Interner> Extend<&'b str> for StrBuilder<'a, I> {
+    fn extend<It: IntoIterator<Item = &'b str>>(&mut self, iter: It) {
+        iter.into_iter().for_each(|s| self.push_str(s));
+    }
+}
+
+impl<'a, I: Interner> fmt::Write for StrBuilder<'a, I> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.push(c);
+        Ok(())
+    }
+}
+
+impl<'a, I: Interner> fmt::Debug for StrBuilder<'a, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("StrBuilder").field(&self.as_str()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use crate::unsync::DataInterner;
+
+    use super::{BytesBuilder, StrBuilder};
+
+    #[test]
+    fn finalize_reuses_allocation() {
+        let interner = DataInterner::new();
+        let mut builder = StrBuilder::with_capacity(&interner, 64);
+        write!(builder, "fn f{}() {{}}", 0).unwrap();
+        let ptr = builder.as_ptr();
+        let code = builder.finalize();
+        assert_eq!(code, "fn f0() {}");
+        assert_eq!(code.as_ptr(), ptr);
+        assert_eq!(interner.find_str("f0()"), Some("f0()"));
+    }
+
+    #[test]
+    fn finalize_dedup_adds_missing() {
+        let interner = DataInterner::new();
+        let mut builder = BytesBuilder::new(&interner);
+        builder.extend(b"abc");
+        let first = builder.finalize_dedup();
+        let mut builder = BytesBuilder::new(&interner);
+        builder.extend(b"abc");
+        let second = builder.finalize_dedup();
+        assert!(std::ptr::eq(first, second));
+        assert_eq!(interner.stats().entries_added, 1);
+    }
+
+    #[test]
+    fn str_pop_and_truncate() {
+        let interner = DataInterner::new();
+        let mut builder = StrBuilder::new(&interner);
+        builder.push_str("añb");
+        assert_eq!(builder.pop(), Some('b'));
+        assert_eq!(builder.pop(), Some('ñ'));
+        builder.truncate(5);
+        assert_eq!(builder.finalize(), "a");
+    }
+}
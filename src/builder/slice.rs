@@ -0,0 +1,796 @@
+use std::{marker::PhantomData, mem, ops, ptr};
+
+use bytemuck::NoUninit;
+
+#[cfg(feature = "yoke")]
+use crate::util::RcInterner;
+use crate::util::{align_offset, Interner};
+#[cfg(feature = "yoke")]
+use yoke::Yoke;
+
+/// The storage shared by [`SliceBuilder`] and [`YokedSliceBuilder`]: `T`s stored in a `Vec<u8>` at an aligned offset,
+/// so that the `Vec` can be given to an interner without copying.
+struct RawSliceBuilder<T: NoUninit> {
+    // Capacity should always be at least size_of::<T>() * self.cap + (align_of::<T>() - 1)
+    data: Vec<u8>,
+    // Byte index into data
+    start: usize,
+    // Element length
+    len: usize,
+    // Element capacity
+    cap: usize,
+    _phantom: PhantomData<Vec<T>>,
+}
+
+impl<T: NoUninit> RawSliceBuilder<T> {
+    fn new() -> Self {
+        let cap = if mem::size_of::<T>() == 0 {
+            usize::MAX
+        } else {
+            0
+        };
+        Self {
+            data: vec![],
+            start: 0,
+            len: 0,
+            cap,
+            _phantom: PhantomData,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut T {
+        if mem::size_of::<T>() == 0 || self.cap == 0 {
+            return ptr::NonNull::dangling().as_ptr();
+        }
+        self.data.as_mut_ptr().wrapping_add(self.start).cast()
+    }
+
+    fn as_ptr(&self) -> *const T {
+        if mem::size_of::<T>() == 0 || self.cap == 0 {
+            return ptr::NonNull::dangling().as_ptr();
+        }
+        self.data.as_ptr().wrapping_add(self.start).cast()
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        let requested_cap = self.len.checked_add(additional).expect("capacity overflow");
+        if requested_cap <= self.cap {
+            // Already sufficient
+            return;
+        }
+        if mem::size_of::<T>() == 0 {
+            // ZST already has max capacity
+            panic!("capacity overflow");
+        }
+        // Always leave room for the maximum alignment padding, since reallocating may change the padding required.
+        let required_raw_cap = requested_cap
+            .checked_mul(mem::size_of::<T>())
+            .and_then(|raw_cap| raw_cap.checked_add(mem::align_of::<T>() - 1))
+            .expect("capacity overflow");
+        self.data.reserve(required_raw_cap - self.data.len());
+
+        let ptr = self.data.as_mut_ptr();
+        // SAFETY: align_of is a power of two
+        let new_align_offset = unsafe { align_offset(mem::align_of::<T>(), ptr) };
+        if self.start != new_align_offset {
+            // Re-align Ts, if there are any (the vec may have been moved to an address with different alignment).
+            let src = ptr.wrapping_add(self.start);
+            let dst = ptr.wrapping_add(new_align_offset);
+            unsafe {
+                // SAFETY: src is valid for reads of self.len * size_of::<T>() bytes, and the vec has capacity for
+                // (align_of::<T>() - 1) + self.len * size_of::<T>() bytes, so dst is valid for writes of that many bytes.
+                // ptr::copy allows the regions to overlap.
+                ptr::copy(src, dst, self.len * mem::size_of::<T>());
+
+                // Prevent having uninit bytes in the initial part of the vec
+                // SAFETY: new_align_offset < align_of::<T>(), so this is within the capacity, and before dst.
+                ptr.write_bytes(0, new_align_offset);
+
+                // SAFETY: all bytes up to this length were just initialized.
+                self.data
+                    .set_len(new_align_offset + self.len * mem::size_of::<T>());
+            }
+            self.start = new_align_offset;
+        }
+        self.cap = (self.data.capacity() - self.start) / mem::size_of::<T>();
+        debug_assert!(
+            self.cap >= requested_cap,
+            "{} >= {} (raw_cap = {}, align_offset = {})",
+            self.cap,
+            requested_cap,
+            self.data.capacity(),
+            self.start,
+        );
+    }
+
+    fn extend_from_slice(&mut self, slice: &[T]) {
+        if mem::size_of::<T>() == 0 {
+            self.len = self
+                .len
+                .checked_add(slice.len())
+                .expect("capacity overflow");
+            return;
+        }
+        self.reserve(slice.len());
+        // NOTE: extend_from_slice will not reallocate, since the capacity is sufficient.
+        self.data.extend_from_slice(bytemuck::cast_slice(slice));
+        self.len += slice.len();
+    }
+
+    fn insert(&mut self, index: usize, value: T) {
+        assert!(
+            index <= self.len,
+            "insertion index (is {index}) should be <= len (is {})",
+            self.len
+        );
+        self.push(value);
+        self.as_mut_slice()[index..].rotate_right(1);
+    }
+
+    fn remove(&mut self, index: usize) -> T {
+        assert!(
+            index < self.len,
+            "removal index (is {index}) should be < len (is {})",
+            self.len
+        );
+        self.as_mut_slice()[index..].rotate_left(1);
+        self.pop().expect("not empty")
+    }
+
+    fn swap_remove(&mut self, index: usize) -> T {
+        assert!(
+            index < self.len,
+            "swap_remove index (is {index}) should be < len (is {})",
+            self.len
+        );
+        let last = self.len - 1;
+        self.as_mut_slice().swap(index, last);
+        self.pop().expect("not empty")
+    }
+
+    fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        self.len = len;
+        if mem::size_of::<T>() != 0 {
+            self.data.truncate(self.start + len * mem::size_of::<T>());
+        }
+    }
+
+    fn resize(&mut self, new_len: usize, value: T) {
+        if new_len <= self.len {
+            self.truncate(new_len);
+        } else {
+            self.reserve(new_len - self.len);
+            while self.len < new_len {
+                self.push(value);
+            }
+        }
+    }
+
+    fn extend(&mut self, iter: impl IntoIterator<Item = T>) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        iter.for_each(|value| self.push(value));
+    }
+
+    fn retain(&mut self, mut f: impl FnMut(&T) -> bool) {
+        let slice = self.as_mut_slice();
+        let mut kept = 0;
+        for i in 0..slice.len() {
+            if f(&slice[i]) {
+                slice[kept] = slice[i];
+                kept += 1;
+            }
+        }
+        self.truncate(kept);
+    }
+
+    fn finalize<I: Interner + ?Sized>(self, interner: &I) -> &[T] {
+        if mem::size_of::<T>() == 0 {
+            let ptr = ptr::NonNull::dangling().as_ptr();
+            // SAFETY: ZSTs can dangle
+            unsafe {
+                return std::slice::from_raw_parts(ptr, self.len);
+            }
+        }
+        if self.is_empty() {
+            return &[];
+        }
+        let data = interner.add_owned_bytes_at(self.data, self.start);
+        unsafe {
+            // SAFETY: if self is not empty, &data[start] is aligned for T and is valid for reads for self.len * size_of::<T>() bytes
+            let ptr = data.as_ptr().cast();
+            std::slice::from_raw_parts(ptr, self.len)
+        }
+    }
+
+    fn finalize_find_or_add<I: Interner + ?Sized>(self, interner: &I) -> &[T] {
+        match interner.find_slice(self.as_slice()) {
+            Some(existing) => existing,
+            None => self.finalize(interner),
+        }
+    }
+
+    fn push(&mut self, value: T) {
+        if self.len == self.cap {
+            // Reallocate
+            self.reserve(1);
+        }
+
+        let old_raw_len = self.start + self.len * mem::size_of::<T>();
+        let new_raw_len = old_raw_len + mem::size_of::<T>();
+        // SAFETY: ptr is valid for size_of::<T>() bytes write and is aligned.
+        unsafe {
+            let ptr = self.data.as_mut_ptr().wrapping_add(old_raw_len);
+            std::ptr::write(ptr.cast(), value)
+        }
+
+        self.len += 1;
+        // SAFETY: we wrote to the added bytes
+        unsafe {
+            self.data.set_len(new_raw_len);
+        }
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.len.checked_sub(1).map(|new_len| {
+            self.len = new_len;
+            let new_raw_len = self.start + self.len * mem::size_of::<T>();
+            let data = &self.data[new_raw_len..][..mem::size_of::<T>()];
+            // SAFETY: data is valid for size_of::<T>() bytes read and is aligned.
+            let value = unsafe { std::ptr::read(data.as_ptr() as *const T) };
+            // SAFETY: reducing length
+            unsafe {
+                self.data.set_len(new_raw_len);
+            }
+            value
+        })
+    }
+}
+
+impl<T: NoUninit> RawSliceBuilder<T> {
+    fn as_slice(&self) -> &[T] {
+        if mem::size_of::<T>() == 0 {
+            let ptr = ptr::NonNull::dangling().as_ptr();
+            // SAFETY: ZSTs can dangle
+            unsafe { std::slice::from_raw_parts(ptr, self.len) }
+        } else if self.len == 0 {
+            &[]
+        } else {
+            let data = &self.data[self.start..];
+            unsafe {
+                // SAFETY: if self is not empty, &data[start] is aligned for T and is valid for reads for self.len * size_of::<T>() bytes
+                let ptr = data.as_ptr().cast();
+                std::slice::from_raw_parts(ptr, self.len)
+            }
+        }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [T] {
+        if mem::size_of::<T>() == 0 {
+            let ptr = ptr::NonNull::dangling().as_ptr();
+            // SAFETY: ZSTs can dangle
+            unsafe { std::slice::from_raw_parts_mut(ptr, self.len) }
+        } else if self.len == 0 {
+            &mut []
+        } else {
+            let data = &mut self.data[self.start..];
+            unsafe {
+                // SAFETY: &data[start] is aligned for T and is valid for reads for self.len * size_of::<T>() bytes
+                let ptr = data.as_mut_ptr().cast();
+                std::slice::from_raw_parts_mut(ptr, self.len)
+            }
+        }
+    }
+}
+
+/// Forwards the `Vec`-like methods of a builder to its `raw` [`RawSliceBuilder`].
+macro_rules! forward_raw_methods {
+    () => {
+        pub fn len(&self) -> usize {
+            self.raw.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.raw.is_empty()
+        }
+
+        pub fn capacity(&self) -> usize {
+            self.raw.capacity()
+        }
+
+        pub fn as_mut_ptr(&mut self) -> *mut T {
+            self.raw.as_mut_ptr()
+        }
+
+        pub fn as_ptr(&self) -> *const T {
+            self.raw.as_ptr()
+        }
+
+        pub fn reserve(&mut self, additional: usize) {
+            self.raw.reserve(additional)
+        }
+
+        pub fn extend_from_slice(&mut self, slice: &[T]) {
+            self.raw.extend_from_slice(slice)
+        }
+
+        pub fn push(&mut self, value: T) {
+            self.raw.push(value)
+        }
+
+        pub fn pop(&mut self) -> Option<T> {
+            self.raw.pop()
+        }
+
+        pub fn insert(&mut self, index: usize, value: T) {
+            self.raw.insert(index, value)
+        }
+
+        pub fn remove(&mut self, index: usize) -> T {
+            self.raw.remove(index)
+        }
+
+        pub fn swap_remove(&mut self, index: usize) -> T {
+            self.raw.swap_remove(index)
+        }
+
+        pub fn truncate(&mut self, len: usize) {
+            self.raw.truncate(len)
+        }
+
+        pub fn clear(&mut self) {
+            self.raw.truncate(0)
+        }
+
+        pub fn resize(&mut self, new_len: usize, value: T) {
+            self.raw.resize(new_len, value)
+        }
+
+        pub fn retain(&mut self, f: impl FnMut(&T) -> bool) {
+            self.raw.retain(f)
+        }
+
+        pub fn as_slice(&self) -> &[T] {
+            self.raw.as_slice()
+        }
+
+        pub fn as_mut_slice(&mut self) -> &mut [T] {
+            self.raw.as_mut_slice()
+        }
+    };
+}
+
+/// A `Vec`-like buffer of `T`s that can be built in place and then added to an interner without copying.
+///
+/// The elements are stored suitably aligned in a byte buffer, which is given to the interner on finalizing.
+/// Only the elements become an entry; the alignment padding before them does not.
+///
+/// # Example
+/// ```rust
+/// use interner::{builder::SliceBuilder, unsync::DataInterner};
+/// let interner = DataInterner::new();
+/// let mut builder = SliceBuilder::from_iter_in(1..=5u64, &interner);
+/// builder.retain(|&x| x % 2 == 1);
+/// builder.insert(0, 0);
+/// builder.extend([7, 9]);
+/// let slice: &[u64] = builder.finalize();
+/// assert_eq!(slice, [0, 1, 3, 5, 7, 9]);
+/// ```
+pub struct SliceBuilder<'a, T: NoUninit, I: Interner> {
+    raw: RawSliceBuilder<T>,
+    interner: &'a I,
+}
+
+impl<'a, T: NoUninit, I: Interner> SliceBuilder<'a, T, I> {
+    pub fn new(interner: &'a I) -> Self {
+        Self {
+            raw: RawSliceBuilder::new(),
+            interner,
+        }
+    }
+
+    pub fn with_capacity(capacity: usize, interner: &'a I) -> Self {
+        let mut this = Self::new(interner);
+        this.reserve(capacity);
+        this
+    }
+
+    /// Creates a builder for `interner` containing the elements of `iter`.
+    pub fn from_iter_in(iter: impl IntoIterator<Item = T>, interner: &'a I) -> Self {
+        let mut this = Self::new(interner);
+        this.extend(iter);
+        this
+    }
+
+    forward_raw_methods!();
+
+    /// Adds the built slice to the interner, returning a reference to it.
+    ///
+    /// The slice is always added, even if equal data already exists in the interner.
+    pub fn finalize(self) -> &'a [T] {
+        self.raw.finalize(self.interner)
+    }
+
+    /// Returns a reference to data in the interner equal to the built slice, adding it with
+    /// [`finalize`](Self::finalize) only if it does not yet exist (or is not sufficiently aligned).
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, builder::SliceBuilder, unsync::DataInterner};
+    /// let interner = DataInterner::new();
+    /// let existing = interner.add_slice(&[1u32, 2, 3]);
+    /// let mut builder = SliceBuilder::new(&interner);
+    /// builder.extend_from_slice(&[1, 2, 3]);
+    /// assert!(std::ptr::eq(builder.finalize_find_or_add(), existing));
+    /// ```
+    pub fn finalize_find_or_add(self) -> &'a [T] {
+        self.raw.finalize_find_or_add(self.interner)
+    }
+}
+
+impl<'a, T: NoUninit, I: Interner> Extend<T> for SliceBuilder<'a, T, I> {
+    fn extend<It: IntoIterator<Item = T>>(&mut self, iter: It) {
+        self.raw.extend(iter)
+    }
+}
+
+impl<'a, 'b, T: NoUninit, I: Interner> Extend<&'b T> for SliceBuilder<'a, T, I> {
+    fn extend<It: IntoIterator<Item = &'b T>>(&mut self, iter: It) {
+        self.raw.extend(iter.into_iter().copied())
+    }
+}
+
+impl<'a, T: NoUninit, I: Interner> ops::Deref for SliceBuilder<'a, T, I> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.raw.as_slice()
+    }
+}
+
+impl<'a, T: NoUninit, I: Interner> ops::DerefMut for SliceBuilder<'a, T, I> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.raw.as_mut_slice()
+    }
+}
+
+/// A [`SliceBuilder`] that holds a clone of an [`RcInterner`] handle instead of borrowing the interner,
+/// created with [`RcInterner::yoked_slice_builder`].
+///
+/// Finalizing it returns a [`Yoke`] that keeps the interner alive, so the built slice can outlive any borrow.
+///
+/// # Example
+/// ```rust
+/// use std::rc::Rc;
+/// use interner::{RcInterner, unsync::DataInterner};
+/// let interner = Rc::new(DataInterner::new());
+/// let mut builder = interner.yoked_slice_builder::<u32>();
+/// builder.push(1);
+/// builder.extend_from_slice(&[2, 3]);
+/// builder[0] = 0;
+/// let slice = builder.finalize();
+/// drop(interner);
+/// assert_eq!(*slice.get(), [0, 2, 3]);
+/// ```
+#[cfg(feature = "yoke")]
+pub struct YokedSliceBuilder<T: NoUninit, R: RcInterner>
+where
+    <R as ops::Deref>::Target: Interner,
+{
+    raw: RawSliceBuilder<T>,
+    interner: R,
+}
+
+#[cfg(feature = "yoke")]
+impl<T: NoUninit, R: RcInterner> YokedSliceBuilder<T, R>
+where
+    <R as ops::Deref>::Target: Interner,
+{
+    pub fn new(interner: R) -> Self {
+        Self {
+            raw: RawSliceBuilder::new(),
+            interner,
+        }
+    }
+
+    pub fn with_capacity(capacity: usize, interner: R) -> Self {
+        let mut this = Self::new(interner);
+        this.reserve(capacity);
+        this
+    }
+
+    /// Creates a builder for `interner` containing the elements of `iter`.
+    pub fn from_iter_in(iter: impl IntoIterator<Item = T>, interner: R) -> Self {
+        let mut this = Self::new(interner);
+        this.extend(iter);
+        this
+    }
+
+    forward_raw_methods!();
+
+    /// Adds the built slice to the interner, returning it yoked to the interner handle.
+    ///
+    /// The slice is always added, even if equal data already exists in the interner.
+    pub fn finalize(self) -> Yoke<&'static [T], R> {
+        let raw = self.raw;
+        Yoke::attach_to_cart(self.interner, |interner| raw.finalize(interner))
+    }
+
+    /// Returns data in the interner equal to the built slice, yoked to the interner handle,
+    /// adding it with [`finalize`](Self::finalize) only if it does not yet exist (or is not sufficiently aligned).
+    pub fn finalize_find_or_add(self) -> Yoke<&'static [T], R> {
+        let raw = self.raw;
+        Yoke::attach_to_cart(self.interner, |interner| raw.finalize_find_or_add(interner))
+    }
+}
+
+#[cfg(feature = "yoke")]
+impl<T: NoUninit, R: RcInterner> Extend<T> for YokedSliceBuilder<T, R>
+where
+    <R as ops::Deref>::Target: Interner,
+{
+    fn extend<It: IntoIterator<Item = T>>(&mut self, iter: It) {
+        self.raw.extend(iter)
+    }
+}
+
+#[cfg(feature = "yoke")]
+impl<'b, T: NoUninit, R: RcInterner> Extend<&'b T> for YokedSliceBuilder<T, R>
+where
+    <R as ops::Deref>::Target: Interner,
+{
+    fn extend<It: IntoIterator<Item = &'b T>>(&mut self, iter: It) {
+        self.raw.extend(iter.into_iter().copied())
+    }
+}
+
+#[cfg(feature = "yoke")]
+impl<T: NoUninit, R: RcInterner> ops::Deref for YokedSliceBuilder<T, R>
+where
+    <R as ops::Deref>::Target: Interner,
+{
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.raw.as_slice()
+    }
+}
+
+#[cfg(feature = "yoke")]
+impl<T: NoUninit, R: RcInterner> ops::DerefMut for YokedSliceBuilder<T, R>
+where
+    <R as ops::Deref>::Target: Interner,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.raw.as_mut_slice()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem;
+
+    use bytemuck::NoUninit;
+
+    use crate::{unsync::DataInterner, InternerConfig, LookupMode};
+
+    use super::{RawSliceBuilder, SliceBuilder};
+
+    #[test]
+    fn usize() {
+        let interner = DataInterner::new();
+        let mut slice_builder = SliceBuilder::<usize, _>::new(&interner);
+        slice_builder.push(0);
+        slice_builder.push(0x5555555555555555);
+        slice_builder.push(0xAAAAAAAAAAAAAAAA);
+        slice_builder.push(0);
+        slice_builder.push(0);
+        slice_builder.push(0x5555555555555555);
+        slice_builder.push(0xAAAAAAAAAAAAAAAA);
+        slice_builder.push(0);
+
+        let slice = slice_builder.finalize();
+        assert_eq!(
+            slice,
+            [
+                0,
+                0x5555555555555555,
+                0xAAAAAAAAAAAAAAAA,
+                0,
+                0,
+                0x5555555555555555,
+                0xAAAAAAAAAAAAAAAA,
+                0
+            ]
+        );
+    }
+
+    #[cfg(feature = "yoke")]
+    #[test]
+    fn yoked_outlives_handle() {
+        use std::sync::Arc;
+
+        use crate::{sync::DataInterner, RcInterner};
+
+        let interner = Arc::new(DataInterner::new());
+        let mut slice_builder = interner.yoked_slice_builder::<u64>();
+        for i in 0..100 {
+            slice_builder.push(i * 0x0101010101010101);
+        }
+        assert_eq!(slice_builder.pop(), Some(99 * 0x0101010101010101));
+        let slice = slice_builder.finalize();
+        assert_eq!(Arc::strong_count(&interner), 2);
+        drop(interner);
+        assert_eq!(slice.get().len(), 99);
+        assert!(slice
+            .get()
+            .iter()
+            .enumerate()
+            .all(|(i, &value)| value == i as u64 * 0x0101010101010101));
+        assert_eq!(
+            slice.get().as_ptr() as usize % std::mem::align_of::<u64>(),
+            0
+        );
+    }
+
+    /// Returns a builder holding `values` at byte offset `start`, which may not be aligned for `T`,
+    /// as if the builder's buffer had been moved to an address with different alignment.
+    fn raw_at_offset<T: NoUninit>(values: &[T], start: usize) -> RawSliceBuilder<T> {
+        let mut data = vec![0; start];
+        data.extend_from_slice(bytemuck::cast_slice(values));
+        data.shrink_to_fit();
+        RawSliceBuilder {
+            start,
+            len: values.len(),
+            // Full, so that the next push must reserve.
+            cap: values.len(),
+            data,
+            _phantom: Default::default(),
+        }
+    }
+
+    fn check_realignment<T: NoUninit + PartialEq + std::fmt::Debug>(values: &[T], extra: T) {
+        for start in 0..mem::align_of::<T>() {
+            let mut raw = raw_at_offset(values, start);
+            raw.push(extra);
+            assert_eq!(raw.as_ptr() as usize % mem::align_of::<T>(), 0);
+            assert_eq!(&raw.as_slice()[..values.len()], values);
+            assert_eq!(raw.as_slice()[values.len()], extra);
+            assert!(raw.data.capacity() >= raw.start + raw.cap * mem::size_of::<T>());
+            assert_eq!(raw.data.len(), raw.start + raw.len * mem::size_of::<T>());
+        }
+    }
+
+    #[test]
+    fn reserve_realigns_u64() {
+        let values: Vec<u64> = (0..37).map(|i| i * 0x0102030405060708).collect();
+        check_realignment(&values, u64::MAX);
+    }
+
+    #[test]
+    fn reserve_realigns_u128() {
+        let values: Vec<u128> = (0..37)
+            .map(|i| i * 0x0102030405060708090A0B0C0D0E0F10)
+            .collect();
+        check_realignment(&values, u128::MAX);
+    }
+
+    #[test]
+    fn vec_parity() {
+        let interner = DataInterner::new();
+        let mut slice_builder = SliceBuilder::<u128, _>::with_capacity(3, &interner);
+        let mut vec = Vec::with_capacity(3);
+        assert!(slice_builder.capacity() >= 3);
+        for i in 0..50u128 {
+            slice_builder.extend_from_slice(&[i, !i]);
+            vec.extend_from_slice(&[i, !i]);
+            if i % 7 == 0 {
+                slice_builder.insert(i as usize, i << 64);
+                vec.insert(i as usize, i << 64);
+            }
+            if i % 5 == 0 {
+                assert_eq!(
+                    slice_builder.remove(i as usize / 2),
+                    vec.remove(i as usize / 2)
+                );
+                assert_eq!(slice_builder.swap_remove(0), vec.swap_remove(0));
+            }
+            assert_eq!(*slice_builder, vec);
+        }
+        slice_builder.retain(|x| x % 3 != 0);
+        vec.retain(|x| x % 3 != 0);
+        assert_eq!(*slice_builder, vec);
+        slice_builder.truncate(20);
+        vec.truncate(20);
+        slice_builder.resize(25, 1);
+        vec.resize(25, 1);
+        slice_builder.extend(&[2, 3]);
+        vec.extend(&[2, 3]);
+        assert_eq!(*slice_builder, vec);
+
+        let slice = slice_builder.finalize();
+        assert_eq!(slice, vec);
+        assert_eq!(slice.as_ptr() as usize % mem::align_of::<u128>(), 0);
+
+        let mut slice_builder = SliceBuilder::from_iter_in(vec.iter().copied(), &interner);
+        assert!(!std::ptr::eq(slice_builder.as_slice(), slice));
+        slice_builder.clear();
+        slice_builder.extend(vec.iter().copied());
+        assert!(std::ptr::eq(slice_builder.finalize_find_or_add(), slice));
+        assert_eq!(interner.stats().entries_added, 1);
+    }
+
+    #[repr(C, align(64))]
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    struct OverAligned([u8; 64]);
+
+    // SAFETY: OverAligned is a Copy, repr(C) wrapper around a [u8; 64], so it has no padding.
+    unsafe impl NoUninit for OverAligned {}
+
+    #[test]
+    fn leading_padding_is_not_part_of_the_entry() {
+        let interner =
+            DataInterner::with_config(InternerConfig::new().lookup_mode(LookupMode::ExactEntry));
+        let values = [OverAligned([1; 64]), OverAligned([2; 64])];
+
+        // Place the elements at a nonzero aligned offset, as `reserve` would for most allocations.
+        let mut data = Vec::<u8>::with_capacity(3 * 64 + 63);
+        let start = match data.as_ptr() as usize % 64 {
+            0 => 64,
+            misalignment => 64 - misalignment,
+        };
+        data.resize(start, 0);
+        data.extend_from_slice(bytemuck::cast_slice(&values));
+        let raw = RawSliceBuilder {
+            data,
+            start,
+            len: values.len(),
+            cap: values.len(),
+            _phantom: Default::default(),
+        };
+        let slice = raw.finalize(&interner);
+        assert_eq!(slice, values);
+
+        let entries: Vec<&[u8]> = interner.iter_bytes().collect();
+        assert_eq!(entries, [bytemuck::cast_slice::<_, u8>(&values)]);
+        assert_eq!(interner.stats().padding_bytes, start);
+        assert!(std::ptr::eq(interner.find_slice(&values).unwrap(), slice));
+
+        let mut slice_builder = SliceBuilder::new(&interner);
+        slice_builder.extend_from_slice(&values);
+        assert!(std::ptr::eq(slice_builder.finalize_find_or_add(), slice));
+        assert_eq!(interner.stats().entries_added, 1);
+    }
+
+    #[test]
+    fn zst_and_empty() {
+        let interner = DataInterner::new();
+        let mut slice_builder = SliceBuilder::<(), _>::new(&interner);
+        slice_builder.extend_from_slice(&[(); 5]);
+        slice_builder.insert(2, ());
+        slice_builder.truncate(4);
+        assert_eq!(slice_builder.finalize().len(), 4);
+
+        let mut slice_builder = SliceBuilder::<u64, _>::with_capacity(10, &interner);
+        slice_builder.push(1);
+        slice_builder.clear();
+        assert_eq!(slice_builder.finalize(), &[] as &[u64]);
+        assert_eq!(interner.find_bytes(&[0]), None);
+        assert_eq!(interner.stats().entries_added, 0);
+    }
+}
@@ -0,0 +1,185 @@
+/// How lookups (e.g. [`Interner::find_bytes`](crate::Interner::find_bytes)) match interned data.
+///
+/// This also affects the `find_or_add_*` methods, which only add data if a lookup fails.
+///
+/// # Example
+/// ```rust
+/// use interner::{Interner, LookupMode, unsync::DataInterner};
+///
+/// let exact = DataInterner::with_lookup_mode(LookupMode::ExactEntry);
+/// let substring = DataInterner::with_lookup_mode(LookupMode::Substring);
+/// let cross_entry = DataInterner::with_lookup_mode(LookupMode::CrossEntrySubstring);
+/// for interner in [&exact, &substring, &cross_entry] {
+///     interner.add_str("Hello, world!");
+///     interner.add_str("Lorem ipsum");
+/// }
+///
+/// assert_eq!(exact.find_str("Hello, world!"), Some("Hello, world!"));
+/// assert_eq!(exact.find_str("Hello"), None);
+/// assert_eq!(exact.find_str("world!Lorem"), None);
+///
+/// assert_eq!(substring.find_str("Hello, world!"), Some("Hello, world!"));
+/// assert_eq!(substring.find_str("Hello"), Some("Hello"));
+/// assert_eq!(substring.find_str("world!Lorem"), None);
+///
+/// assert_eq!(cross_entry.find_str("Hello, world!"), Some("Hello, world!"));
+/// assert_eq!(cross_entry.find_str("Hello"), Some("Hello"));
+/// assert_eq!(cross_entry.find_str("world!Lorem"), Some("world!Lorem"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LookupMode {
+    /// Only data equal to an entire previously-added entry is found.
+    ///
+    /// Lookups use a hash index, so they take O(1) average time.
+    ExactEntry,
+    /// Data equal to any part of a single previously-added entry is found.
+    ///
+    /// Lookups scan all entries.
+    Substring,
+    /// Data equal to any part of the interner's buffers is found, even if it spans multiple entries
+    /// (or alignment padding between them).
+    ///
+    /// Lookups scan all buffers. This is the default.
+    #[default]
+    CrossEntrySubstring,
+}
+
+/// Configuration for a [`sync::DataInterner`](crate::sync::DataInterner) or [`unsync::DataInterner`](crate::unsync::DataInterner).
+///
+/// Interned data is stored in chunks which are never reallocated. The first chunk allocated has
+/// [`initial_chunk_size`](Self::initial_chunk_size()) bytes, and each subsequent chunk is
+/// [`growth_factor`](Self::growth_factor()) times larger than the last, up to [`max_chunk_size`](Self::max_chunk_size()).
+/// Data of at least [`large_object_threshold`](Self::large_object_threshold()) bytes is stored in its own exactly-sized allocation instead,
+/// and does not affect the size of subsequent chunks.
+///
+/// New data is placed in the chunk with the least remaining space that can hold it ("best fit").
+/// Once a chunk has less than [`retire_threshold`](Self::retire_threshold()) bytes remaining, its remaining space is filled with padding
+/// and the chunk is no longer considered for new data.
+///
+/// # Example
+/// ```rust
+/// use interner::{Interner, InternerConfig, LookupMode, unsync::DataInterner};
+///
+/// let config = InternerConfig::new()
+///     .initial_chunk_size(64)
+///     .growth_factor(4)
+///     .max_chunk_size(1 << 20)
+///     .large_object_threshold(1 << 16)
+///     .lookup_mode(LookupMode::ExactEntry);
+/// let interner = DataInterner::with_config(config);
+/// assert_eq!(interner.config(), config);
+/// let hello = interner.add_str("Hello, world!");
+/// assert_eq!(interner.find_str("Hello, world!"), Some(hello));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InternerConfig {
+    pub(crate) lookup_mode: LookupMode,
+    pub(crate) initial_chunk_size: usize,
+    pub(crate) growth_factor: usize,
+    pub(crate) max_chunk_size: usize,
+    pub(crate) large_object_threshold: usize,
+    pub(crate) retire_threshold: usize,
+    pub(crate) budget: Option<usize>,
+}
+
+impl InternerConfig {
+    /// The default configuration: [`LookupMode::CrossEntrySubstring`], 1 KiB initial chunks doubling up to 64 KiB,
+    /// separate allocations for data of at least 4 KiB, retiring chunks with less than 16 bytes remaining, and no memory budget.
+    pub const fn new() -> Self {
+        Self {
+            lookup_mode: LookupMode::CrossEntrySubstring,
+            initial_chunk_size: 1024,
+            growth_factor: 2,
+            max_chunk_size: 64 * 1024,
+            large_object_threshold: 4 * 1024,
+            retire_threshold: 16,
+            budget: None,
+        }
+    }
+
+    /// Sets the [`LookupMode`].
+    pub const fn lookup_mode(self, lookup_mode: LookupMode) -> Self {
+        Self {
+            lookup_mode,
+            ..self
+        }
+    }
+
+    /// Sets the size in bytes of the first chunk allocated.
+    pub const fn initial_chunk_size(self, initial_chunk_size: usize) -> Self {
+        Self {
+            initial_chunk_size,
+            ..self
+        }
+    }
+
+    /// Sets the factor each chunk size is multiplied by to get the next chunk size.
+    ///
+    /// A growth factor of `0` or `1` disables growth, so all chunks have the initial size.
+    pub const fn growth_factor(self, growth_factor: usize) -> Self {
+        Self {
+            growth_factor,
+            ..self
+        }
+    }
+
+    /// Sets the size in bytes that chunk sizes stop growing at.
+    ///
+    /// If this is less than the initial chunk size, all chunks have the initial size.
+    pub const fn max_chunk_size(self, max_chunk_size: usize) -> Self {
+        Self {
+            max_chunk_size,
+            ..self
+        }
+    }
+
+    /// Sets the size in bytes at or above which data is stored in its own exactly-sized allocation instead of in a chunk.
+    pub const fn large_object_threshold(self, large_object_threshold: usize) -> Self {
+        Self {
+            large_object_threshold,
+            ..self
+        }
+    }
+
+    /// Sets the remaining space in bytes below which a chunk is retired and no longer considered for new data.
+    ///
+    /// Larger thresholds waste more space at the end of each chunk, but keep fewer partially-filled chunks around.
+    /// A chunk is always retired once it is completely full.
+    pub const fn retire_threshold(self, retire_threshold: usize) -> Self {
+        Self {
+            retire_threshold,
+            ..self
+        }
+    }
+
+    /// Sets the maximum total capacity in bytes of all buffers the interner allocates (or takes ownership of).
+    ///
+    /// Once the budget is exhausted, fallible methods like [`Interner::try_add_bytes`](crate::Interner::try_add_bytes)
+    /// return [`InternerError::BudgetExceeded`](crate::InternerError::BudgetExceeded), and infallible methods like
+    /// [`Interner::add_bytes`](crate::Interner::add_bytes) panic. Chunks are shrunk to fit in the remaining budget if necessary.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, InternerConfig, InternerError, unsync::DataInterner};
+    ///
+    /// let interner = DataInterner::with_config(InternerConfig::new().budget(16));
+    /// assert_eq!(interner.try_add_str("Hello, world!"), Ok("Hello, world!"));
+    /// assert_eq!(interner.try_find_or_add_str("world"), Ok("world"));
+    /// assert!(matches!(
+    ///     interner.try_add_str("Lorem ipsum"),
+    ///     Err(InternerError::BudgetExceeded { .. }),
+    /// ));
+    /// ```
+    pub const fn budget(self, budget: usize) -> Self {
+        Self {
+            budget: Some(budget),
+            ..self
+        }
+    }
+}
+
+impl Default for InternerConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,135 @@
+use std::fmt;
+
+use crate::{IStr, Interner, InternerError};
+
+/// An object-safe subset of [`Interner`], covering byte and string data only.
+///
+/// [`Interner`]'s slice and value methods are generic, so with the `bytemuck` feature enabled `dyn Interner` cannot be used.
+/// Every [`Interner`] implements `DynInterner`, so an interner of any type can be held as e.g. `Box<dyn DynInterner>`.
+///
+/// # Example
+/// ```rust
+/// use std::sync::Arc;
+/// use interner::{DynInterner, sync, unsync};
+/// let interners: Vec<Box<dyn DynInterner>> = vec![
+///     Box::new(unsync::DataInterner::new()),
+///     Box::new(Arc::new(sync::DataInterner::new())),
+/// ];
+/// for interner in &interners {
+///     let greeting = interner.add_str("Hello, world!");
+///     assert_eq!(interner.find_str("Hello"), Some("Hello"));
+///     assert_eq!(greeting, "Hello, world!");
+/// }
+/// ```
+pub trait DynInterner {
+    /// See [`Interner::try_clear`].
+    fn try_clear(&mut self) -> Result<(), InternerError>;
+    /// See [`Interner::find_bytes`].
+    fn find_bytes(&self, value: &[u8]) -> Option<&[u8]>;
+    /// See [`Interner::owns`].
+    fn owns(&self, value: &[u8]) -> bool;
+    /// See [`Interner::owns_str`].
+    fn owns_str(&self, value: &str) -> bool;
+    /// See [`Interner::find_or_add_bytes`].
+    fn find_or_add_bytes(&self, value: &[u8]) -> &[u8];
+    /// See [`Interner::try_find_or_add_bytes`].
+    fn try_find_or_add_bytes(&self, value: &[u8]) -> Result<&[u8], InternerError>;
+    /// See [`Interner::add_bytes`].
+    fn add_bytes(&self, value: &[u8]) -> &[u8];
+    /// See [`Interner::try_add_bytes`].
+    fn try_add_bytes(&self, value: &[u8]) -> Result<&[u8], InternerError>;
+    /// See [`Interner::add_owned_bytes`].
+    fn add_owned_bytes(&self, value: Vec<u8>) -> &[u8];
+    /// See [`Interner::find_str`].
+    fn find_str(&self, value: &str) -> Option<&str>;
+    /// See [`Interner::find_or_add_str`].
+    fn find_or_add_str(&self, value: &str) -> &str;
+    /// See [`Interner::try_find_or_add_str`].
+    fn try_find_or_add_str(&self, value: &str) -> Result<&str, InternerError>;
+    /// See [`Interner::add_str`].
+    fn add_str(&self, value: &str) -> &str;
+    /// See [`Interner::try_add_str`].
+    fn try_add_str(&self, value: &str) -> Result<&str, InternerError>;
+    /// See [`Interner::add_owned_string`].
+    fn add_owned_string(&self, value: String) -> &str;
+    /// See [`Interner::find_or_add_istr`].
+    fn find_or_add_istr(&self, value: &str) -> IStr<'_>;
+    /// See [`Interner::add_fmt`].
+    fn add_fmt(&self, args: fmt::Arguments<'_>) -> &str;
+    /// See [`Interner::find_or_add_fmt`].
+    fn find_or_add_fmt(&self, args: fmt::Arguments<'_>) -> &str;
+}
+
+impl<I: Interner + ?Sized> DynInterner for I {
+    fn try_clear(&mut self) -> Result<(), InternerError> {
+        Interner::try_clear(self)
+    }
+
+    fn find_bytes(&self, value: &[u8]) -> Option<&[u8]> {
+        Interner::find_bytes(self, value)
+    }
+
+    fn owns(&self, value: &[u8]) -> bool {
+        Interner::owns(self, value)
+    }
+
+    fn owns_str(&self, value: &str) -> bool {
+        Interner::owns_str(self, value)
+    }
+
+    fn find_or_add_bytes(&self, value: &[u8]) -> &[u8] {
+        Interner::find_or_add_bytes(self, value)
+    }
+
+    fn try_find_or_add_bytes(&self, value: &[u8]) -> Result<&[u8], InternerError> {
+        Interner::try_find_or_add_bytes(self, value)
+    }
+
+    fn add_bytes(&self, value: &[u8]) -> &[u8] {
+        Interner::add_bytes(self, value)
+    }
+
+    fn try_add_bytes(&self, value: &[u8]) -> Result<&[u8], InternerError> {
+        Interner::try_add_bytes(self, value)
+    }
+
+    fn add_owned_bytes(&self, value: Vec<u8>) -> &[u8] {
+        Interner::add_owned_bytes(self, value)
+    }
+
+    fn find_str(&self, value: &str) -> Option<&str> {
+        Interner::find_str(self, value)
+    }
+
+    fn find_or_add_str(&self, value: &str) -> &str {
+        Interner::find_or_add_str(self, value)
+    }
+
+    fn try_find_or_add_str(&self, value: &str) -> Result<&str, InternerError> {
+        Interner::try_find_or_add_str(self, value)
+    }
+
+    fn add_str(&self, value: &str) -> &str {
+        Interner::add_str(self, value)
+    }
+
+    fn try_add_str(&self, value: &str) -> Result<&str, InternerError> {
+        Interner::try_add_str(self, value)
+    }
+
+    fn add_owned_string(&self, value: String) -> &str {
+        Interner::add_owned_string(self, value)
+    }
+
+    fn find_or_add_istr(&self, value: &str) -> IStr<'_> {
+        Interner::find_or_add_istr(self, value)
+    }
+
+    fn add_fmt(&self, args: fmt::Arguments<'_>) -> &str {
+        Interner::add_fmt(self, args)
+    }
+
+    fn find_or_add_fmt(&self, args: fmt::Arguments<'_>) -> &str {
+        Interner::find_or_add_fmt(self, args)
+    }
+}
@@ -0,0 +1,130 @@
+use std::{collections::TryReserveError, fmt};
+
+/// An error returned by the fallible methods of [`Interner`](crate::Interner), e.g. [`try_add_bytes`](crate::Interner::try_add_bytes).
+///
+/// # Example
+/// ```rust
+/// use std::rc::Rc;
+/// use interner::{Interner, InternerError, unsync::DataInterner};
+///
+/// fn reset(interner: &mut Rc<DataInterner>) -> Result<(), InternerError> {
+///     interner.try_clear()?;
+///     Ok(())
+/// }
+///
+/// let mut interner = Rc::new(DataInterner::new());
+/// let other = Rc::clone(&interner);
+/// assert_eq!(reset(&mut interner), Err(InternerError::Shared));
+/// drop(other);
+/// assert_eq!(reset(&mut interner), Ok(()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InternerError {
+    /// The interner is shared (e.g. an `Rc` with other strong references), so it cannot be cleared.
+    Shared,
+    /// Adding the data would exceed the interner's memory budget (see [`InternerConfig::budget`](crate::InternerConfig::budget())).
+    BudgetExceeded {
+        /// The number of bytes that would need to be allocated.
+        required: usize,
+        /// The number of bytes remaining in the budget.
+        available: usize,
+    },
+    /// Allocating a buffer failed.
+    AllocFailed(TryReserveError),
+    /// The data cannot be stored in place because its type is not 1-aligned (see [`Interner::try_add_owned`](crate::Interner::try_add_owned)).
+    Misaligned {
+        /// The alignment the data's type requires.
+        align: usize,
+    },
+    /// The interner is read-only, so data cannot be added and it cannot be cleared.
+    Frozen,
+}
+
+impl fmt::Display for InternerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InternerError::Shared => f.write_str("interner is shared"),
+            InternerError::BudgetExceeded {
+                required,
+                available,
+            } => write!(
+                f,
+                "interner memory budget exceeded ({required} bytes required, {available} bytes available)"
+            ),
+            InternerError::AllocFailed(_) => f.write_str("interner buffer allocation failed"),
+            InternerError::Misaligned { align } => write!(
+                f,
+                "owned data with alignment {align} cannot be stored in place"
+            ),
+            InternerError::Frozen => f.write_str("interner is read-only"),
+        }
+    }
+}
+
+impl std::error::Error for InternerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InternerError::AllocFailed(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<TryReserveError> for InternerError {
+    fn from(error: TryReserveError) -> Self {
+        InternerError::AllocFailed(error)
+    }
+}
+
+/// The error returned by [`Interner::try_add_owned`](crate::Interner::try_add_owned), which gives back the value that could not be added.
+#[cfg(feature = "bytemuck")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytemuck")))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TryAddOwnedError<T> {
+    pub(crate) error: InternerError,
+    pub(crate) value: Vec<T>,
+}
+
+#[cfg(feature = "bytemuck")]
+impl<T> TryAddOwnedError<T> {
+    /// Returns the reason the value could not be added.
+    pub fn error(&self) -> &InternerError {
+        &self.error
+    }
+
+    /// Returns the value that could not be added.
+    pub fn into_value(self) -> Vec<T> {
+        self.value
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<T> fmt::Display for TryAddOwnedError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<T: fmt::Debug> std::error::Error for TryAddOwnedError<T> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.error.source()
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<T> From<TryAddOwnedError<T>> for InternerError {
+    fn from(error: TryAddOwnedError<T>) -> Self {
+        error.error
+    }
+}
+
+/// Returns the value of a fallible add operation, panicking on failure, for the infallible methods of [`Interner`](crate::Interner).
+#[track_caller]
+pub(crate) fn expect_added<T>(result: Result<T, InternerError>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(error) => panic!("failed to add data to interner: {error}"),
+    }
+}
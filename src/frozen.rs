@@ -0,0 +1,807 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+};
+
+use memchr::memmem::Finder;
+
+use crate::{
+    config::{InternerConfig, LookupMode},
+    error::{expect_added, InternerError},
+    inner::{
+        find_aligned,
+        snapshot::{
+            invalid_data, read_buffer_header, read_entry, read_header, read_symbol, read_u64,
+            BufferHeader, SnapshotSymbol,
+        },
+        DataInternerInner, SymbolType,
+    },
+    stats::InternerStats,
+    symbol::Symbol,
+    util::{is_aligned_to, make_inherent_impls, pointer_alignment},
+    Interner,
+};
+
+#[cfg(feature = "bytemuck")]
+use crate::{
+    error::TryAddOwnedError,
+    symbol::{cast_symbol_data, TypedSymbol},
+};
+#[cfg(feature = "bytemuck")]
+use bytemuck::{cast_slice, NoUninit};
+#[cfg(feature = "bytemuck")]
+use std::{mem::size_of, ptr::NonNull};
+
+/// A read-only interner that looks up data in a snapshot in place, without copying it.
+///
+/// The snapshot is written by [`unsync::DataInterner::write_snapshot`](crate::unsync::DataInterner::write_snapshot)
+/// or [`sync::DataInterner::write_snapshot`](crate::sync::DataInterner::write_snapshot), and is borrowed,
+/// e.g. from a memory-mapped file or a `&'static [u8]`. Lookups behave as they did in the interner that wrote the snapshot,
+/// and [`Symbol`]s created by that interner can be resolved.
+///
+/// Data cannot be added: the fallible `try_*` methods of [`Interner`] return [`InternerError::Frozen`],
+/// the infallible `add_*` methods panic, and the `find_or_add_*` methods panic if the data is not found.
+///
+/// The snapshot must be placed in memory at an address aligned to 256 bytes (as a memory-mapped file is),
+/// so that interned data keeps the alignment it had when the snapshot was written.
+///
+/// # Example
+/// ```rust
+/// use interner::{FrozenInterner, Interner, InternerError, unsync::DataInterner};
+///
+/// #[repr(C, align(256))]
+/// struct Aligned([u8; 4096]);
+///
+/// let interner = DataInterner::new();
+/// let hello = interner.intern_str("Hello, world!");
+/// let mut snapshot = Box::new(Aligned([0; 4096]));
+/// let mut writer = &mut snapshot.0[..];
+/// interner.write_snapshot(&mut writer).unwrap();
+///
+/// let frozen = FrozenInterner::from_snapshot(&snapshot.0).unwrap();
+/// assert_eq!(frozen.resolve(hello), "Hello, world!");
+/// assert_eq!(frozen.find_str("world"), Some("world"));
+/// assert_eq!(frozen.find_or_add_str("Hello"), "Hello");
+/// assert_eq!(frozen.try_add_str("Goodbye"), Err(InternerError::Frozen));
+/// ```
+pub struct FrozenInterner<'a> {
+    config: InternerConfig,
+    /// The data of each buffer in the snapshot, in order of address.
+    buffers: Vec<&'a [u8]>,
+    /// Every entry in the snapshot, in insertion order.
+    entries: Vec<&'a [u8]>,
+    /// Exact-match index of every entry, keeping the most-aligned of equal entries. Only present for [`LookupMode::ExactEntry`].
+    index: Option<HashSet<&'a [u8]>>,
+    /// The string referred to by each [`Symbol`], or `None` for symbols that were not interned as strings.
+    symbols: Vec<Option<&'a str>>,
+    symbol_index: HashMap<&'a str, Symbol>,
+}
+
+/// Splits the first `len` bytes off of `reader`.
+fn take<'a>(reader: &mut &'a [u8], len: usize) -> io::Result<&'a [u8]> {
+    if reader.len() < len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    let (taken, rest) = reader.split_at(len);
+    *reader = rest;
+    Ok(taken)
+}
+
+impl<'a> FrozenInterner<'a> {
+    /// Validates the snapshot in `snapshot` and constructs a `FrozenInterner` that looks up data in it.
+    ///
+    /// This reads every entry and symbol to build the interner's index, but never copies interned data.
+    /// An error of kind [`InvalidData`](io::ErrorKind::InvalidData) is returned if the snapshot is malformed,
+    /// was written by an unsupported version of this crate, or is not sufficiently aligned in memory.
+    /// Snapshots in version `1` of the format cannot be read in place, since their buffers are not aligned within the snapshot;
+    /// read them with [`unsync::DataInterner::read_snapshot`](crate::unsync::DataInterner::read_snapshot) instead.
+    pub fn from_snapshot(snapshot: &'a [u8]) -> io::Result<Self> {
+        let reader = &mut { snapshot };
+        let header = read_header(reader)?;
+        if header.version < 2 {
+            // Older snapshots do not align buffers within the snapshot, so they can only be copied with `read_snapshot`.
+            return Err(invalid_data(
+                "interner snapshot version cannot be read in place",
+            ));
+        }
+        let config = header.config;
+
+        let mut buffers = vec![];
+        for _ in 0..read_u64(reader)? {
+            let BufferHeader {
+                align,
+                misalignment,
+                len,
+                padding,
+            } = read_buffer_header(reader, header.version)?;
+            take(reader, padding)?;
+            let data = take(reader, len)?;
+            if data.as_ptr() as usize % align != misalignment {
+                return Err(invalid_data(
+                    "interner snapshot is not sufficiently aligned in memory",
+                ));
+            }
+            buffers.push(data);
+        }
+
+        let mut entries = vec![];
+        let mut index = match config.lookup_mode {
+            LookupMode::ExactEntry => Some(HashSet::<&'a [u8]>::new()),
+            LookupMode::Substring | LookupMode::CrossEntrySubstring => None,
+        };
+        for _ in 0..read_u64(reader)? {
+            let entry = read_entry(reader, &buffers)?;
+            entries.push(entry);
+            let Some(index) = &mut index else {
+                continue;
+            };
+            match index.get(entry) {
+                Some(existing)
+                    if pointer_alignment(existing.as_ptr())
+                        >= pointer_alignment(entry.as_ptr()) => {}
+                _ => {
+                    index.replace(entry);
+                }
+            }
+        }
+
+        let mut symbols = vec![];
+        let mut symbol_index = HashMap::new();
+        for i in 0..read_u64(reader)? {
+            let symbol = match read_symbol(reader, &buffers, header.version)? {
+                SnapshotSymbol::Str(symbol) => Some(symbol),
+                SnapshotSymbol::Typed { .. } | SnapshotSymbol::Unknown => None,
+            };
+            if let Some(symbol) = symbol {
+                symbol_index.insert(symbol, Symbol::from_index(i));
+            }
+            symbols.push(symbol);
+        }
+
+        Ok(Self {
+            config,
+            buffers,
+            entries,
+            index,
+            symbols,
+            symbol_index,
+        })
+    }
+
+    /// Returns the configuration of the interner that wrote the snapshot.
+    pub fn config(&self) -> InternerConfig {
+        self.config
+    }
+
+    /// Returns the [`LookupMode`] of the interner that wrote the snapshot.
+    pub fn lookup_mode(&self) -> LookupMode {
+        self.config.lookup_mode
+    }
+
+    /// Returns the number of entries in the snapshot.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the snapshot has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over every entry in the snapshot, in the order they were added.
+    pub fn iter_bytes(&self) -> impl Iterator<Item = &'a [u8]> + '_ {
+        self.entries.iter().copied()
+    }
+
+    /// Return the [`Symbol`] for data equal to `value`, if the interner that wrote the snapshot interned it with `intern_str`.
+    ///
+    /// See [`unsync::DataInterner::find_symbol`](crate::unsync::DataInterner::find_symbol).
+    pub fn find_symbol(&self, value: &str) -> Option<Symbol> {
+        self.symbol_index.get(value).copied()
+    }
+
+    /// Return the string referred to by `symbol`.
+    ///
+    /// Panics if `symbol` was not created by the interner that wrote the snapshot. See [`try_resolve`](FrozenInterner::try_resolve) for a non-panicking version.
+    pub fn resolve(&self, symbol: Symbol) -> &'a str {
+        self.try_resolve(symbol)
+            .expect("symbol does not belong to this interner")
+    }
+
+    /// Return the string referred to by `symbol`, if it was created by the interner that wrote the snapshot.
+    pub fn try_resolve(&self, symbol: Symbol) -> Option<&'a str> {
+        *self.symbols.get(symbol.index())?
+    }
+
+    fn owns_bytes(&self, value: &[u8]) -> bool {
+        if value.is_empty() {
+            return true;
+        }
+        let start = value.as_ptr() as usize;
+        // Buffers are in order of address, since they were read from one snapshot.
+        let i = self
+            .buffers
+            .partition_point(|buffer| buffer.as_ptr() as usize <= start);
+        match i.checked_sub(1).map(|i| self.buffers[i]) {
+            Some(buffer) => start + value.len() <= buffer.as_ptr() as usize + buffer.len(),
+            None => false,
+        }
+    }
+
+    /// SAFETY: align must be a power of two.
+    unsafe fn find_bytes_with_align(&self, value: &[u8], align: usize) -> Option<&'a [u8]> {
+        match self.config.lookup_mode {
+            LookupMode::ExactEntry => {
+                let index = self
+                    .index
+                    .as_ref()
+                    .expect("ExactEntry interners have an index");
+                let found: &'a [u8] = index.get(value)?;
+                // SAFETY: align is a power of two.
+                unsafe { is_aligned_to(align, found.as_ptr()) }.then_some(found)
+            }
+            LookupMode::Substring => {
+                let finder = Finder::new(value);
+                self.entries
+                    .iter()
+                    // SAFETY: align is a power of two.
+                    .find_map(|&entry| unsafe { find_aligned(&finder, entry, align) })
+            }
+            LookupMode::CrossEntrySubstring => {
+                let finder = Finder::new(value);
+                self.buffers
+                    .iter()
+                    // SAFETY: align is a power of two.
+                    .find_map(|&buffer| unsafe { find_aligned(&finder, buffer, align) })
+            }
+        }
+    }
+}
+
+/// Implements [`Interner`] for a read-only interner type, which must have the inherent methods
+/// `unsafe fn find_bytes_with_align(&self, value: &[u8], align: usize) -> Option<&[u8]>` (where `align` must be a power of two)
+/// and `fn owns_bytes(&self, value: &[u8]) -> bool`.
+macro_rules! read_only_interner_impl {
+    (unsafe impl Interner for $ty:ty) => {
+        unsafe impl Interner for $ty {
+            /// A frozen interner can never be cleared, so this always returns [`InternerError::Frozen`].
+            fn try_clear(&mut self) -> Result<(), InternerError> {
+                Err(InternerError::Frozen)
+            }
+
+            fn find_bytes(&self, value: &[u8]) -> Option<&[u8]> {
+                if value.is_empty() {
+                    return Some(&[]);
+                }
+                // SAFETY: 1 is a power of two.
+                unsafe { self.find_bytes_with_align(value, 1) }
+            }
+
+            fn owns(&self, value: &[u8]) -> bool {
+                self.owns_bytes(value)
+            }
+
+            fn find_or_add_bytes(&self, value: &[u8]) -> &[u8] {
+                expect_added(self.try_find_or_add_bytes(value))
+            }
+
+            /// Returns [`InternerError::Frozen`] if data equal to `value` is not found.
+            fn try_find_or_add_bytes(&self, value: &[u8]) -> Result<&[u8], InternerError> {
+                self.find_bytes(value).ok_or(InternerError::Frozen)
+            }
+
+            fn add_bytes(&self, value: &[u8]) -> &[u8] {
+                expect_added(self.try_add_bytes(value))
+            }
+
+            /// Always returns [`InternerError::Frozen`].
+            fn try_add_bytes(&self, _value: &[u8]) -> Result<&[u8], InternerError> {
+                Err(InternerError::Frozen)
+            }
+
+            /// Panics unless `value.capacity() == 0`.
+            fn add_owned_bytes(&self, value: Vec<u8>) -> &[u8] {
+                if value.capacity() == 0 {
+                    // Ignore empty buffers
+                    return &[];
+                }
+                expect_added(Err(InternerError::Frozen))
+            }
+
+            #[cfg(feature = "bytemuck")]
+            fn find_slice<T: NoUninit + 'static>(&self, value: &[T]) -> Option<&[T]> {
+                if value.is_empty() {
+                    // Ignore empty slices
+                    Some(&[])
+                } else if size_of::<T>() == 0 {
+                    // Ignore ZSTs
+                    // SAFETY: T is a ZST
+                    unsafe {
+                        let ptr = NonNull::dangling();
+                        Some(std::slice::from_raw_parts(ptr.as_ptr(), value.len()))
+                    }
+                } else {
+                    let len = value.len();
+                    let value: &[u8] = cast_slice(value);
+                    let align = std::mem::align_of::<T>();
+                    // SAFETY: align is a power of two.
+                    let found = unsafe { self.find_bytes_with_align(value, align)? };
+                    // This would require T: AnyBitPattern, but that is more restrictive than necessary, since we know the bit pattern matches the original
+                    // Some(cast_slice(found))
+                    unsafe {
+                        let ptr = found.as_ptr();
+                        let ptr = ptr as *const T;
+                        // SAFETY: T is Copy has no interior mutability, and ptr points to equal bytes as value did.
+                        Some(std::slice::from_raw_parts(ptr, len))
+                    }
+                }
+            }
+
+            #[cfg(feature = "bytemuck")]
+            fn find_or_add_slice<T: NoUninit + 'static>(&self, value: &[T]) -> &[T] {
+                expect_added(self.try_find_or_add_slice(value))
+            }
+
+            /// Returns [`InternerError::Frozen`] if data bytewise-equal to `value` is not found.
+            #[cfg(feature = "bytemuck")]
+            fn try_find_or_add_slice<T: NoUninit + 'static>(
+                &self,
+                value: &[T],
+            ) -> Result<&[T], InternerError> {
+                self.find_slice(value).ok_or(InternerError::Frozen)
+            }
+
+            #[cfg(feature = "bytemuck")]
+            fn add_slice<T: NoUninit + 'static>(&self, value: &[T]) -> &[T] {
+                expect_added(self.try_add_slice(value))
+            }
+
+            /// Always returns [`InternerError::Frozen`].
+            #[cfg(feature = "bytemuck")]
+            fn try_add_slice<T: NoUninit + 'static>(
+                &self,
+                _value: &[T],
+            ) -> Result<&[T], InternerError> {
+                Err(InternerError::Frozen)
+            }
+
+            /// Returns [`InternerError::Frozen`] unless `value.capacity() == 0`.
+            #[cfg(feature = "bytemuck")]
+            fn try_add_owned<T: NoUninit + 'static>(
+                &self,
+                value: Vec<T>,
+            ) -> Result<&[T], TryAddOwnedError<T>> {
+                if value.capacity() == 0 {
+                    // Ignore empty buffers
+                    return Ok(&[]);
+                }
+                Err(TryAddOwnedError {
+                    error: InternerError::Frozen,
+                    value,
+                })
+            }
+
+            #[cfg(feature = "bytemuck")]
+            fn find_value<T: NoUninit + 'static>(&self, value: &T) -> Option<&T> {
+                Some(&self.find_slice(std::slice::from_ref(value))?[0])
+            }
+
+            #[cfg(feature = "bytemuck")]
+            fn find_or_add_value<T: NoUninit + 'static>(&self, value: &T) -> &T {
+                &self.find_or_add_slice(std::slice::from_ref(value))[0]
+            }
+
+            #[cfg(feature = "bytemuck")]
+            fn add_value<T: NoUninit + 'static>(&self, value: &T) -> &T {
+                &self.add_slice(std::slice::from_ref(value))[0]
+            }
+        }
+    };
+}
+
+read_only_interner_impl!(unsafe impl Interner for FrozenInterner<'_>);
+
+/// A read-only interner that owns the data of a [`DataInterner`](crate::unsync::DataInterner), created by
+/// [`unsync::DataInterner::freeze`](crate::unsync::DataInterner::freeze) or [`sync::DataInterner::freeze`](crate::sync::DataInterner::freeze).
+///
+/// Since no data can be added, lookups need no lock or [`RefCell`](std::cell::RefCell), so a `FrozenDataInterner` is `Send` and `Sync`
+/// and can be shared between threads (e.g. in an [`Arc`](std::sync::Arc)) without contention.
+/// Lookups behave as they did in the interner it was frozen from, and [`Symbol`]s and [`TypedSymbol`]s created by that interner can be resolved.
+///
+/// As with [`FrozenInterner`], the fallible `try_*` methods of [`Interner`] return [`InternerError::Frozen`],
+/// the infallible `add_*` methods panic, and the `find_or_add_*` methods panic if the data is not found.
+///
+/// # Example
+/// ```rust
+/// use interner::{Interner, InternerError, unsync::DataInterner};
+/// use std::sync::Arc;
+///
+/// let interner = DataInterner::new();
+/// let hello = interner.intern_str("Hello, world!");
+/// let frozen = Arc::new(interner.freeze());
+///
+/// let thread = std::thread::spawn({
+///     let frozen = Arc::clone(&frozen);
+///     move || frozen.find_str("world").is_some()
+/// });
+/// assert!(thread.join().unwrap());
+/// assert_eq!(frozen.resolve(hello), "Hello, world!");
+/// assert_eq!(frozen.try_add_str("Goodbye"), Err(InternerError::Frozen));
+/// ```
+pub struct FrozenDataInterner {
+    inner: DataInternerInner,
+}
+
+impl FrozenDataInterner {
+    pub(crate) fn new(inner: DataInternerInner) -> Self {
+        Self { inner }
+    }
+
+    /// Returns the configuration of the interner this was frozen from.
+    pub fn config(&self) -> InternerConfig {
+        self.inner.config()
+    }
+
+    /// Returns how lookups in this interner match interned data.
+    pub fn lookup_mode(&self) -> LookupMode {
+        self.inner.lookup_mode()
+    }
+
+    /// Returns statistics about this interner's memory usage.
+    ///
+    /// See [`unsync::DataInterner::stats`](crate::unsync::DataInterner::stats).
+    pub fn stats(&self) -> InternerStats {
+        self.inner.stats()
+    }
+
+    /// Returns the number of entries in this interner.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if this interner has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over the data of every entry in this interner, in the order they were added.
+    pub fn iter_bytes(&self) -> impl Iterator<Item = &[u8]> + '_ {
+        // SAFETY: No buffer is invalidated while self is borrowed, since data is never added to or removed from a frozen interner.
+        (0..).map_while(move |index| unsafe { self.inner.entry(index) })
+    }
+
+    /// Returns an iterator over every entry in this interner that is valid UTF-8, in the order they were added.
+    pub fn iter_strs(&self) -> impl Iterator<Item = &str> + '_ {
+        self.iter_bytes()
+            .filter_map(|entry| std::str::from_utf8(entry).ok())
+    }
+
+    /// Return the [`Symbol`] for data equal to `value`, if the interner this was frozen from interned it with `intern_str`.
+    ///
+    /// See [`unsync::DataInterner::find_symbol`](crate::unsync::DataInterner::find_symbol).
+    pub fn find_symbol(&self, value: &str) -> Option<Symbol> {
+        self.inner.find_symbol(value.as_bytes(), SymbolType::str())
+    }
+
+    /// Return the string referred to by `symbol`.
+    ///
+    /// Panics if `symbol` was not created by the interner this was frozen from. See [`try_resolve`](FrozenDataInterner::try_resolve) for a non-panicking version.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        self.try_resolve(symbol)
+            .expect("symbol does not belong to this interner")
+    }
+
+    /// Return the string referred to by `symbol`, if it was created by the interner this was frozen from.
+    pub fn try_resolve(&self, symbol: Symbol) -> Option<&str> {
+        // SAFETY: No buffer is invalidated while self is borrowed.
+        let owned = unsafe { self.inner.resolve(symbol, SymbolType::str())? };
+        // SAFETY: Symbols are only created from valid utf8.
+        Some(unsafe { std::str::from_utf8_unchecked(owned) })
+    }
+
+    /// Return the [`TypedSymbol`] for data bytewise-equal to `value`, if the interner this was frozen from interned it with `intern_slice` or `intern_value`.
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytemuck")))]
+    pub fn find_slice_symbol<T: NoUninit + 'static>(&self, value: &[T]) -> Option<TypedSymbol<T>> {
+        let symbol = self
+            .inner
+            .find_symbol(cast_slice(value), SymbolType::of::<T>())?;
+        Some(TypedSymbol::new(symbol))
+    }
+
+    /// Return the slice referred to by `symbol`.
+    ///
+    /// Panics if `symbol` was not created by the interner this was frozen from. See [`try_resolve_slice`](FrozenDataInterner::try_resolve_slice) for a non-panicking version.
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytemuck")))]
+    pub fn resolve_slice<T: NoUninit + 'static>(&self, symbol: TypedSymbol<T>) -> &[T] {
+        self.try_resolve_slice(symbol)
+            .expect("symbol does not belong to this interner")
+    }
+
+    /// Return the slice referred to by `symbol`, if it was created by the interner this was frozen from.
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytemuck")))]
+    pub fn try_resolve_slice<T: NoUninit + 'static>(&self, symbol: TypedSymbol<T>) -> Option<&[T]> {
+        // SAFETY: No buffer is invalidated while self is borrowed.
+        let owned = unsafe { self.inner.resolve(symbol.symbol(), SymbolType::of::<T>())? };
+        // SAFETY: Symbols of type T are only created from [T] with T's alignment, and T is not zero-sized.
+        Some(unsafe { cast_symbol_data(owned) })
+    }
+
+    /// Return the value referred to by `symbol`.
+    ///
+    /// Panics if `symbol` was not created by the interner this was frozen from, or does not refer to exactly one value.
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytemuck")))]
+    pub fn resolve_value<T: NoUninit + 'static>(&self, symbol: TypedSymbol<T>) -> &T {
+        match self.resolve_slice(symbol) {
+            [value] => value,
+            _ => panic!("symbol does not refer to exactly one value"),
+        }
+    }
+
+    fn owns_bytes(&self, value: &[u8]) -> bool {
+        self.inner.owns(value)
+    }
+
+    /// SAFETY: align must be a power of two.
+    unsafe fn find_bytes_with_align(&self, value: &[u8], align: usize) -> Option<&[u8]> {
+        // SAFETY: No buffer is invalidated while self is borrowed, and align is a power of two.
+        unsafe { self.inner.find_bytes_with_align(value, align) }
+    }
+}
+
+read_only_interner_impl!(unsafe impl Interner for FrozenDataInterner);
+
+make_inherent_impls! {
+    impl FrozenInterner<'_> {
+        pub fn find_bytes(&self, value: &[u8]) -> Option<&[u8]>;
+        pub fn owns(&self, value: &[u8]) -> bool;
+        pub fn owns_str(&self, value: &str) -> bool;
+        pub fn find_str(&self, value: &str) -> Option<&str>;
+        pub fn find_or_add_str(&self, value: &str) -> &str;
+        pub fn try_find_or_add_str(&self, value: &str) -> Result<&str, InternerError>;
+
+        #[cfg(feature = "bytemuck")]
+        pub fn find_slice[T: NoUninit + 'static](&self, value: &[T]) -> Option<&[T]>;
+        #[cfg(feature = "bytemuck")]
+        pub fn find_value[T: NoUninit + 'static](&self, value: &T) -> Option<&T>;
+    }
+}
+
+make_inherent_impls! {
+    impl FrozenDataInterner {
+        pub fn find_bytes(&self, value: &[u8]) -> Option<&[u8]>;
+        pub fn owns(&self, value: &[u8]) -> bool;
+        pub fn owns_str(&self, value: &str) -> bool;
+        pub fn find_str(&self, value: &str) -> Option<&str>;
+        pub fn find_or_add_str(&self, value: &str) -> &str;
+        pub fn try_find_or_add_str(&self, value: &str) -> Result<&str, InternerError>;
+
+        #[cfg(feature = "bytemuck")]
+        pub fn find_slice[T: NoUninit + 'static](&self, value: &[T]) -> Option<&[T]>;
+        #[cfg(feature = "bytemuck")]
+        pub fn find_value[T: NoUninit + 'static](&self, value: &T) -> Option<&T>;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::{FrozenDataInterner, FrozenInterner};
+    use crate::{unsync::DataInterner, Interner, InternerConfig, InternerError, LookupMode};
+
+    /// Snapshot storage aligned like a memory-mapped file.
+    #[repr(C, align(4096))]
+    struct Page([u8; 4096]);
+
+    fn write(interner: &DataInterner) -> Box<[Page]> {
+        let mut snapshot = vec![];
+        interner.write_snapshot(&mut snapshot).unwrap();
+        let mut pages: Box<[Page]> = (0..snapshot.len().div_ceil(4096))
+            .map(|_| Page([0; 4096]))
+            .collect();
+        for (page, chunk) in pages.iter_mut().zip(snapshot.chunks(4096)) {
+            page.0[..chunk.len()].copy_from_slice(chunk);
+        }
+        pages
+    }
+
+    fn bytes(pages: &[Page]) -> &[u8] {
+        // SAFETY: Page is a u8 array with no padding.
+        unsafe { std::slice::from_raw_parts(pages.as_ptr().cast(), pages.len() * 4096) }
+    }
+
+    #[test]
+    fn lookups_match_the_original_interner() {
+        for lookup_mode in [
+            LookupMode::ExactEntry,
+            LookupMode::Substring,
+            LookupMode::CrossEntrySubstring,
+        ] {
+            let interner = DataInterner::with_config(
+                InternerConfig::new()
+                    .lookup_mode(lookup_mode)
+                    .initial_chunk_size(32),
+            );
+            interner.add_str("Hello, world!");
+            interner.add_str("Lorem ipsum");
+            let symbol = interner.intern_str("dolor");
+            interner.add_bytes(&[b'x'; 100]);
+            let pages = write(&interner);
+            let frozen = FrozenInterner::from_snapshot(bytes(&pages)).unwrap();
+
+            assert_eq!(frozen.lookup_mode(), lookup_mode);
+            assert_eq!(frozen.len(), interner.len());
+            assert!(frozen.iter_bytes().eq(interner.iter_bytes()));
+            for value in ["Hello, world!", "world", "!Lorem", "dolor", "missing", ""] {
+                assert_eq!(
+                    frozen.find_str(value),
+                    interner.find_str(value),
+                    "{value:?}"
+                );
+                if let Some(found) = frozen.find_str(value) {
+                    assert!(frozen.owns_str(found));
+                    assert!(!interner.owns_str(found) || found.is_empty());
+                }
+            }
+            assert!(!frozen.owns_str("dolor"));
+            assert_eq!(frozen.resolve(symbol), "dolor");
+            assert_eq!(frozen.find_symbol("dolor"), Some(symbol));
+            assert_eq!(frozen.find_symbol("Lorem ipsum"), None);
+        }
+    }
+
+    #[test]
+    fn adding_fails() {
+        let interner = DataInterner::new();
+        interner.add_str("abc");
+        let pages = write(&interner);
+        let mut frozen = FrozenInterner::from_snapshot(bytes(&pages)).unwrap();
+        assert_eq!(frozen.try_add_str("abc"), Err(InternerError::Frozen));
+        assert_eq!(frozen.try_find_or_add_str("b"), Ok("b"));
+        assert_eq!(frozen.try_find_or_add_str("d"), Err(InternerError::Frozen));
+        assert_eq!(frozen.add_owned_bytes(vec![]), b"");
+        assert_eq!(frozen.try_clear(), Err(InternerError::Frozen));
+        let result = std::panic::catch_unwind(|| frozen.add_str("d").len());
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn aligned_slices_are_found_in_place() {
+        let interner = DataInterner::with_lookup_mode(LookupMode::ExactEntry);
+        interner.add_str("a");
+        let u64s = interner.add_slice::<u64>(&[1, 2, 3]);
+        interner.add_str("b");
+        let u128s = interner.add_slice::<u128>(&[4, 5]);
+        let pages = write(&interner);
+        let frozen = FrozenInterner::from_snapshot(bytes(&pages)).unwrap();
+        assert_eq!(frozen.find_slice::<u64>(&[1, 2, 3]), Some(u64s));
+        assert_eq!(frozen.find_slice::<u128>(&[4, 5]), Some(u128s));
+        assert_eq!(frozen.find_value(&5u128), None);
+        let found = frozen.find_slice::<u128>(&[4, 5]).unwrap();
+        assert!(frozen.owns(bytemuck::cast_slice(found)));
+        assert_eq!(found.as_ptr() as usize % 16, 0);
+    }
+
+    #[test]
+    fn misaligned_snapshots_are_rejected() {
+        let interner = DataInterner::new();
+        // The start of a buffer is at least 2-aligned.
+        interner.add_str("abc");
+        let mut snapshot = vec![];
+        interner.write_snapshot(&mut snapshot).unwrap();
+        let mut pages: Box<[Page]> = (0..snapshot.len() / 4096 + 1)
+            .map(|_| Page([0; 4096]))
+            .collect();
+        pages[0].0[1..][..snapshot.len()].copy_from_slice(&snapshot);
+        let shifted = &bytes(&pages)[1..][..snapshot.len()];
+        let error = FrozenInterner::from_snapshot(shifted).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(
+            FrozenInterner::from_snapshot(&bytes(&write(&interner))[..snapshot.len() - 1]).is_err()
+        );
+    }
+
+    #[test]
+    fn version_1_snapshots_are_rejected() {
+        let snapshot = include_bytes!("../tests/fixtures/snapshot_v1.bin");
+        let mut pages = [Page([0; 4096])];
+        pages[0].0[..snapshot.len()].copy_from_slice(snapshot);
+        let error = FrozenInterner::from_snapshot(&bytes(&pages)[..snapshot.len()])
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(DataInterner::read_snapshot(&snapshot[..]).is_ok());
+    }
+
+    #[test]
+    fn frozen_data_interner_matches_the_original_interner() {
+        for lookup_mode in [
+            LookupMode::ExactEntry,
+            LookupMode::Substring,
+            LookupMode::CrossEntrySubstring,
+        ] {
+            let interner = crate::sync::DataInterner::with_config(
+                InternerConfig::new()
+                    .lookup_mode(lookup_mode)
+                    .initial_chunk_size(32),
+            );
+            interner.add_str("Hello, world!");
+            interner.add_str("Lorem ipsum");
+            let symbol = interner.intern_str("dolor");
+            interner.add_bytes(&[b'x'; 100]);
+            let expected: Vec<_> = ["Hello, world!", "world", "!Lorem", "dolor", "missing", ""]
+                .into_iter()
+                .map(|value| interner.find_str(value).map(str::to_owned))
+                .collect();
+            let entries: Vec<Vec<u8>> = interner.iter_bytes().map(<[u8]>::to_vec).collect();
+            let stats = interner.stats();
+            let frozen = interner.freeze();
+
+            assert_eq!(frozen.lookup_mode(), lookup_mode);
+            assert_eq!(frozen.stats(), stats);
+            assert!(frozen.iter_bytes().eq(entries.iter().map(Vec::as_slice)));
+            for (value, expected) in ["Hello, world!", "world", "!Lorem", "dolor", "missing", ""]
+                .into_iter()
+                .zip(expected)
+            {
+                let found = frozen.find_str(value);
+                assert_eq!(found, expected.as_deref(), "{value:?}");
+                if let Some(found) = found {
+                    assert!(frozen.owns_str(found));
+                }
+            }
+            assert!(!frozen.owns_str("dolor"));
+            assert_eq!(frozen.resolve(symbol), "dolor");
+            assert_eq!(frozen.find_symbol("dolor"), Some(symbol));
+            assert_eq!(frozen.try_find_or_add_str("dolor"), Ok("dolor"));
+            assert_eq!(frozen.try_add_str("dolor"), Err(InternerError::Frozen));
+        }
+    }
+
+    #[test]
+    fn frozen_data_interner_is_shared_between_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<FrozenDataInterner>();
+
+        let interner = DataInterner::new();
+        let symbols: Vec<_> = (0..100)
+            .map(|i| interner.intern_str(&i.to_string()))
+            .collect();
+        let frozen = interner.freeze();
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for (i, &symbol) in symbols.iter().enumerate() {
+                        assert_eq!(frozen.resolve(symbol), i.to_string());
+                        assert_eq!(frozen.find_symbol(&i.to_string()), Some(symbol));
+                    }
+                });
+            }
+        });
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn frozen_data_interner_resolves_typed_symbols() {
+        let interner = DataInterner::new();
+        let u64s = interner.intern_slice::<u64>(&[1, 2, 3]);
+        let value = interner.intern_value(&0x55555555u32);
+        let frozen = interner.freeze();
+        assert_eq!(frozen.resolve_slice(u64s), &[1, 2, 3]);
+        assert_eq!(frozen.resolve_value(value), &0x55555555u32);
+        assert_eq!(frozen.find_slice_symbol::<u64>(&[1, 2, 3]), Some(u64s));
+        assert_eq!(
+            frozen.find_slice::<u64>(&[1, 2, 3]),
+            Some(frozen.resolve_slice(u64s))
+        );
+        assert_eq!(
+            frozen.try_add_slice::<u64>(&[4]),
+            Err(InternerError::Frozen)
+        );
+    }
+}
@@ -1,27 +1,292 @@
-use crate::util::{align_offset, is_aligned_to};
+use std::{
+    alloc::Layout,
+    any::TypeId,
+    collections::{hash_map::DefaultHasher, BTreeMap, BTreeSet, HashMap, HashSet},
+    hash::{Hash, Hasher},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use memchr::memmem::Finder;
+
+pub(crate) mod snapshot;
+
+use crate::{
+    config::{InternerConfig, LookupMode},
+    error::{expect_added, InternerError},
+    stats::InternerStats,
+    symbol::Symbol,
+    util::{align_offset, is_aligned_to, pointer_alignment},
+};
 
 type FullBuf = Box<[u8]>;
 type NonFullBuf = Vec<u8>;
+type UnboundSymbolIndex = HashMap<Layout, HashMap<&'static [u8], Vec<Symbol>>>;
+
+/// The type a symbol's data was interned as: `str` for strings, `T` for `[T]` slices.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct SymbolType {
+    pub(crate) type_id: TypeId,
+    pub(crate) layout: Layout,
+}
+
+impl SymbolType {
+    #[cfg(any(feature = "bytemuck", test))]
+    pub(crate) fn of<T: 'static>() -> Self {
+        Self {
+            type_id: TypeId::of::<T>(),
+            layout: Layout::new::<T>(),
+        }
+    }
+
+    /// Strings are interned with the layout of their bytes.
+    pub(crate) fn str() -> Self {
+        Self {
+            type_id: TypeId::of::<str>(),
+            layout: Layout::new::<u8>(),
+        }
+    }
+
+    /// A hash of the type's `TypeId`, which is the same wherever one build of a program computes it,
+    /// so it identifies the type in a snapshot read by the build that wrote it.
+    pub(crate) fn id_hash(self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.type_id.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// What is known about the type of a symbol's data.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum SymbolBinding {
+    Bound(SymbolType),
+    /// Read from a snapshot, where the data was interned as a type with this layout and [`id_hash`](SymbolType::id_hash).
+    ///
+    /// The symbol resolves as a type with the same layout and hash, and is bound to the first type with the same layout
+    /// that equal data is looked up or interned as.
+    Unbound {
+        layout: Layout,
+        id_hash: u64,
+    },
+    /// Read from a version 2 snapshot, which does not record the types of symbols that are not strings, so it never resolves.
+    Unknown,
+}
 
-#[derive(Default)]
 pub(crate) struct DataInternerInner {
     /// SAFETY: No DataBuf in these vecs will be dropped, reallocated, or have initialized parts changed during the designated lifetime.
     /// The DataBufs may be moved between the vecs (from nonfull to full),
     /// and the vecs themselves may be reallocated.
     full_buffers: Vec<FullBuf>,
     nonfull_buffers: Vec<NonFullBuf>,
+    /// The remaining capacity of each buffer in `nonfull_buffers`, as `(remaining capacity, index)`, for best-fit placement.
+    ///
+    /// Every buffer in `nonfull_buffers` has an entry here, except while it is being added to.
+    free_space: BTreeSet<(usize, usize)>,
+    config: InternerConfig,
+    /// Capacity of the next chunk to allocate, see [`InternerConfig`].
+    next_chunk_size: usize,
+    /// Every entry added to this interner, in insertion order.
+    ///
+    /// SAFETY: The `'static` references here and in `index` point into the buffers above, so they must not outlive them,
+    /// and must be removed when the buffers are cleared.
+    entries: Vec<&'static [u8]>,
+    /// Exact-match index of every entry added to this interner, keyed on content. Only present for [`LookupMode::ExactEntry`].
+    ///
+    /// If multiple entries have equal content, the one with the greatest pointer alignment is kept,
+    /// so a lookup with a given alignment succeeds if and only if any equal entry is sufficiently aligned.
+    index: Option<HashSet<&'static [u8]>>,
+    /// Data referred to by each [`Symbol`] handed out by this interner, indexed by [`Symbol::index`],
+    /// along with the type the data was interned as.
+    ///
+    /// Each symbol refers to a (not necessarily entire) entry, so symbols can be handed out regardless of the lookup mode.
+    symbols: Vec<(&'static [u8], SymbolBinding)>,
+    /// Maps the type and data of each bound symbol back to the symbol. Allocated when the first symbol is created.
+    symbol_index: Option<HashMap<TypeId, HashMap<&'static [u8], Symbol>>>,
+    /// Maps the layout and data of each unbound symbol back to the symbols, in the order they were created.
+    /// Allocated when the first unbound symbol is read from a snapshot.
+    unbound_symbol_index: Option<UnboundSymbolIndex>,
+    /// The address and capacity of every buffer's allocation, for checking whether data lives in this interner.
+    ///
+    /// Buffers are never reallocated (even when moved from `nonfull_buffers` to `full_buffers`), so these never change.
+    buffer_ranges: BTreeMap<usize, usize>,
+    /// Total capacity of all buffers.
+    capacity: usize,
+    /// Number of bytes in the buffers used for padding.
+    padding_bytes: usize,
+    /// Number of times existing data was found by a `find_or_add` operation.
+    /// Atomic so it can be updated while a [`sync::DataInterner`](crate::sync::DataInterner) is only locked for reading.
+    deduplicated: AtomicUsize,
+}
+
+impl Default for DataInternerInner {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl DataInternerInner {
     pub(crate) const fn new() -> Self {
+        let config = InternerConfig::new();
         Self {
             full_buffers: Vec::new(),
             nonfull_buffers: Vec::new(),
+            free_space: BTreeSet::new(),
+            config,
+            next_chunk_size: config.initial_chunk_size,
+            entries: Vec::new(),
+            index: None,
+            symbols: Vec::new(),
+            symbol_index: None,
+            unbound_symbol_index: None,
+            buffer_ranges: BTreeMap::new(),
+            capacity: 0,
+            padding_bytes: 0,
+            deduplicated: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn with_config(config: InternerConfig) -> Self {
+        let index = match config.lookup_mode {
+            LookupMode::ExactEntry => Some(HashSet::new()),
+            LookupMode::Substring | LookupMode::CrossEntrySubstring => None,
+        };
+        Self {
+            config,
+            next_chunk_size: config.initial_chunk_size,
+            index,
+            ..Self::new()
+        }
+    }
+
+    pub(crate) fn config(&self) -> InternerConfig {
+        self.config
+    }
+
+    pub(crate) fn lookup_mode(&self) -> LookupMode {
+        self.config.lookup_mode
+    }
+
+    /// Replaces this interner's configuration, e.g. to restore the full budget after merging the shards of a
+    /// [`ShardedDataInterner`](crate::sync::ShardedDataInterner). The lookup mode must not change, since the index depends on it.
+    pub(crate) fn set_config(&mut self, config: InternerConfig) {
+        debug_assert_eq!(config.lookup_mode, self.config.lookup_mode);
+        self.config = config;
+    }
+
+    pub(crate) fn stats(&self) -> InternerStats {
+        let full_bytes: usize = self.full_buffers.iter().map(|buffer| buffer.len()).sum();
+        InternerStats {
+            full_buffers: self.full_buffers.len(),
+            nonfull_buffers: self.nonfull_buffers.len(),
+            capacity: self.capacity,
+            bytes_used: full_bytes + self.nonfull_buffers.iter().map(Vec::len).sum::<usize>(),
+            padding_bytes: self.padding_bytes,
+            entries_added: self.entries.len(),
+            entries_deduplicated: self.deduplicated.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns the number of entries added to this interner.
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns the `index`th entry added to this interner, if there is one.
+    // SAFETY: Caller must ensure that buffers are not invalidated within the 'a lifetime.
+    pub(crate) unsafe fn entry<'a>(&self, index: usize) -> Option<&'a [u8]> {
+        self.entries.get(index).copied()
+    }
+
+    /// Records that a `find_or_add` operation found existing data.
+    pub(crate) fn record_deduplicated(&self) {
+        self.deduplicated.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns an error if taking ownership of `required` more bytes of capacity would exceed the budget.
+    pub(crate) fn check_budget(&self, required: usize) -> Result<(), InternerError> {
+        match self.config.budget {
+            Some(budget) if required > budget.saturating_sub(self.capacity) => {
+                Err(InternerError::BudgetExceeded {
+                    required,
+                    available: budget.saturating_sub(self.capacity),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the capacity to allocate for a new buffer that must hold at least `min_capacity` bytes,
+    /// of which `data_len` bytes are data, and advances the chunk size according to the growth policy.
+    ///
+    /// The capacity is reduced to fit in the budget if necessary, or an error is returned if `min_capacity` does not fit.
+    fn new_buffer_capacity(
+        &mut self,
+        data_len: usize,
+        min_capacity: usize,
+    ) -> Result<usize, InternerError> {
+        self.check_budget(min_capacity)?;
+        if data_len >= self.config.large_object_threshold {
+            return Ok(min_capacity);
+        }
+        let mut capacity = self.next_chunk_size.max(min_capacity);
+        if let Some(budget) = self.config.budget {
+            capacity = capacity.min(budget - self.capacity);
+        }
+        let max_chunk_size = self
+            .config
+            .max_chunk_size
+            .max(self.config.initial_chunk_size);
+        self.next_chunk_size = self
+            .next_chunk_size
+            .saturating_mul(self.config.growth_factor.max(1))
+            .min(max_chunk_size);
+        Ok(capacity)
+    }
+
+    /// Moves all buffers, entries, and symbols from `other` into `self`, without moving any interned data.
+    ///
+    /// Symbols from `other` are renumbered, or merged with equal symbols in `self`.
+    pub(crate) fn append(&mut self, other: Self) {
+        self.full_buffers.extend(other.full_buffers);
+        let offset = self.nonfull_buffers.len();
+        self.nonfull_buffers.extend(other.nonfull_buffers);
+        self.free_space.extend(
+            other
+                .free_space
+                .into_iter()
+                .map(|(remaining, i)| (remaining, i + offset)),
+        );
+        for entry in other.entries {
+            self.record_entry(entry);
+        }
+        for (owned, binding) in other.symbols {
+            match binding {
+                SymbolBinding::Bound(ty) => {
+                    if self.find_symbol(owned, ty).is_none() {
+                        self.push_symbol(owned, ty);
+                    }
+                }
+                SymbolBinding::Unbound { .. } | SymbolBinding::Unknown => {
+                    self.push_unbound_symbol(owned, binding);
+                }
+            }
         }
+        self.buffer_ranges.extend(other.buffer_ranges);
+        self.capacity += other.capacity;
+        self.padding_bytes += other.padding_bytes;
+        *self.deduplicated.get_mut() += other.deduplicated.into_inner();
     }
 
     // SAFETY: Caller must ensure that no references to any buffers exist. E.g. by owning or holding a &mut to the outer interner.
     pub(crate) unsafe fn clear(&mut self) {
+        self.symbols.clear();
+        if let Some(symbol_index) = &mut self.symbol_index {
+            symbol_index.clear();
+        }
+        self.unbound_symbol_index = None;
+        self.entries.clear();
+        if let Some(index) = &mut self.index {
+            index.clear();
+        }
         for buffer in &mut self.nonfull_buffers {
             buffer.clear();
         }
@@ -30,6 +295,45 @@ impl DataInternerInner {
             buffer.clear();
             self.nonfull_buffers.push(buffer);
         }
+        self.padding_bytes = 0;
+        *self.deduplicated.get_mut() = 0;
+        self.free_space.clear();
+        self.free_space.extend(
+            self.nonfull_buffers
+                .iter()
+                .enumerate()
+                .map(|(i, buffer)| (buffer.capacity(), i)),
+        );
+    }
+
+    /// Adds the nonfull buffer at index `i` (which must not be in `free_space`) back to `free_space`,
+    /// or retires it to `full_buffers` if its remaining capacity is below the retire threshold.
+    fn file_nonfull_buffer(&mut self, i: usize) {
+        let buffer = &mut self.nonfull_buffers[i];
+        let remaining_capacity = buffer.capacity() - buffer.len();
+        if remaining_capacity > 0 && remaining_capacity >= self.config.retire_threshold {
+            self.free_space.insert((remaining_capacity, i));
+            return;
+        }
+        // Pad the remaining capacity so the buffer can be converted to a boxed slice.
+        // SAFETY: Vec::resize does not reallocate if the new length is at most the capacity.
+        self.padding_bytes += remaining_capacity;
+        buffer.resize(buffer.capacity(), b'\n');
+        let last = self.nonfull_buffers.len() - 1;
+        // Move the buffer to full_buffers.
+        // SAFETY: Vec::into_boxed_slice does not reallocate it's storage IF the length == the capacity.
+        // from docs for std::vec::Vec "If len == capacity, (as is the case for the vec! macro), then a Vec<T> can be converted to and from a Box<[T]>
+        // without reallocating or moving the elements.
+        let newly_full_buffer = self.nonfull_buffers.swap_remove(i);
+        self.full_buffers.push(newly_full_buffer.into_boxed_slice());
+        if i != last {
+            // The last buffer was moved to index i.
+            let moved = &self.nonfull_buffers[i];
+            let remaining_capacity = moved.capacity() - moved.len();
+            let was_present = self.free_space.remove(&(remaining_capacity, last));
+            debug_assert!(was_present, "moved buffer must have been in free_space");
+            self.free_space.insert((remaining_capacity, i));
+        }
     }
 
     // SAFETY: Caller must ensure that buffers are not invalidated within the 'a lifetime.
@@ -39,43 +343,102 @@ impl DataInternerInner {
     }
 
     // SAFETY: Caller must ensure that buffers are not invalidated within the 'a lifetime.
-    pub(crate) unsafe fn add_bytes<'a>(&mut self, value: &[u8]) -> &'a [u8] {
+    pub(crate) unsafe fn try_add_bytes<'a>(
+        &mut self,
+        value: &[u8],
+    ) -> Result<&'a [u8], InternerError> {
         // SAFETY: Same as this function. 1 is a power of 2.
-        unsafe { self.add_bytes_with_align(value, 1) }
+        unsafe { self.try_add_bytes_with_align(value, 1) }
     }
 
     // SAFETY: Caller must ensure that buffers are not invalidated within the 'a lifetime.
     pub(crate) unsafe fn add_owned_bytes<'a>(&mut self, value: Vec<u8>) -> &'a [u8] {
+        // SAFETY: Same safety requirements as this function
+        expect_added(unsafe { self.try_add_owned_bytes(value) })
+    }
+
+    // SAFETY: Caller must ensure that buffers are not invalidated within the 'a lifetime.
+    pub(crate) unsafe fn try_add_owned_bytes<'a>(
+        &mut self,
+        value: Vec<u8>,
+    ) -> Result<&'a [u8], InternerError> {
+        self.check_budget(value.capacity())?;
+        // SAFETY: Same safety requirements as this function
+        let owned = unsafe { self.store_owned_bytes(value) };
+        self.record_entry(owned);
+        Ok(owned)
+    }
+
+    /// Like [`add_owned_bytes`](Self::add_owned_bytes), but the entry starts at byte `start` of `value`,
+    /// and the bytes before it are counted as padding.
+    // SAFETY: Caller must ensure that buffers are not invalidated within the 'a lifetime.
+    pub(crate) unsafe fn add_owned_bytes_at<'a>(
+        &mut self,
+        value: Vec<u8>,
+        start: usize,
+    ) -> &'a [u8] {
+        assert!(start <= value.len(), "entry start out of bounds");
+        expect_added(self.check_budget(value.capacity()));
+        // SAFETY: Same safety requirements as this function
+        let owned = unsafe { self.store_owned_bytes(value) };
+        let entry = &owned[start..];
+        self.padding_bytes += start;
+        self.record_entry(entry);
+        entry
+    }
+
+    /// Adds `entry` to the list of entries and to the index, if there is one.
+    fn record_entry(&mut self, entry: &'static [u8]) {
+        if entry.is_empty() {
+            return;
+        }
+        self.entries.push(entry);
+        let Some(index) = &mut self.index else {
+            return;
+        };
+        match index.get(entry) {
+            Some(existing)
+                if pointer_alignment(existing.as_ptr()) >= pointer_alignment(entry.as_ptr()) => {}
+            _ => {
+                index.replace(entry);
+            }
+        }
+    }
+
+    // SAFETY: Caller must ensure that buffers are not invalidated within the 'static lifetime.
+    unsafe fn store_owned_bytes(&mut self, value: Vec<u8>) -> &'static [u8] {
         if value.capacity() == 0 {
             // Ignore empty buffers
             debug_assert!(value.is_empty());
             &[]
         } else if value.len() == value.capacity() {
             // Add to full_buffers
+            self.track_buffer(&value);
             self.full_buffers.push(value.into_boxed_slice());
-            let owned: &[u8] = &*self.full_buffers.last().expect("just pushed");
+            let owned: &[u8] = self.full_buffers.last().expect("just pushed");
             // SAFETY: The data buffer will never be reallocated
             let owned: &'static [u8] = unsafe { std::mem::transmute(owned) };
             owned
         } else {
             // Add to nonfull_buffers
+            self.track_buffer(&value);
             self.nonfull_buffers.push(value);
-            let owned: &[u8] = &*self.nonfull_buffers.last().expect("just pushed");
+            let owned: &[u8] = self.nonfull_buffers.last().expect("just pushed");
             // SAFETY: The data buffer will never be reallocated
             let owned: &'static [u8] = unsafe { std::mem::transmute(owned) };
+            self.file_nonfull_buffer(self.nonfull_buffers.len() - 1);
             owned
         }
     }
 
     // TODO: Future: Maybe check for prefixes at the end of nonfull buffers.
     // SAFETY: Caller must ensure that buffers are not invalidated within the 'a lifetime.
-    pub(crate) unsafe fn find_or_add_bytes<'a>(&mut self, value: &[u8]) -> &'a [u8] {
-        // SAFETY: Same safety requirements as this function
-        match unsafe { self.find_bytes(value) } {
-            Some(owned) => owned,
-            // SAFETY: Same safety requirements as this function
-            None => unsafe { self.add_bytes(value) },
-        }
+    pub(crate) unsafe fn try_find_or_add_bytes<'a>(
+        &mut self,
+        value: &[u8],
+    ) -> Result<&'a [u8], InternerError> {
+        // SAFETY: Same as this function. 1 is a power of 2.
+        unsafe { self.try_find_or_add_bytes_with_align(value, 1) }
     }
 
     // SAFETY: Caller must ensure that buffers are not invalidated within the 'a lifetime, and that align is a power of two.
@@ -84,26 +447,42 @@ impl DataInternerInner {
         value: &[u8],
         align: usize,
     ) -> Option<&'a [u8]> {
-        // TODO: Maybe use a memchr::memmem::Finder?
-        for buf in &*self.full_buffers {
-            if let Some(idx) = memchr::memmem::find(buf, value) {
-                let owned: &[u8] = &buf[idx..][..value.len()];
+        match self.config.lookup_mode {
+            LookupMode::ExactEntry => {
+                let index = self
+                    .index
+                    .as_ref()
+                    .expect("ExactEntry interners have an index");
+                let owned: &'static [u8] = index.get(value)?;
                 // SAFETY: align is a power of two.
-                if unsafe { !is_aligned_to(align, owned.as_ptr()) } {
-                    continue;
-                }
-                // SAFETY: The string will never be reallocated
-                let owned: &'static [u8] = unsafe { std::mem::transmute(owned) };
-                return Some(owned);
+                unsafe { is_aligned_to(align, owned.as_ptr()) }.then_some(owned)
             }
+            // SAFETY: Same safety requirements as this function
+            LookupMode::Substring => unsafe { self.scan_entries_with_align(value, align) },
+            // SAFETY: Same safety requirements as this function
+            LookupMode::CrossEntrySubstring => unsafe { self.scan_bytes_with_align(value, align) },
         }
-        for buf in &*self.nonfull_buffers {
-            if let Some(idx) = memchr::memmem::find(buf, value) {
-                let owned: &[u8] = &buf[idx..][..value.len()];
-                // SAFETY: align is a power of two.
-                if unsafe { !is_aligned_to(align, owned.as_ptr()) } {
-                    continue;
-                }
+    }
+
+    // Substring search within each entry.
+    // SAFETY: align must be a power of two.
+    unsafe fn scan_entries_with_align(&self, value: &[u8], align: usize) -> Option<&'static [u8]> {
+        let finder = Finder::new(value);
+        self.entries
+            .iter()
+            // SAFETY: align is a power of two.
+            .find_map(|&entry| unsafe { find_aligned(&finder, entry, align) })
+    }
+
+    // Substring search over the raw contents of all buffers.
+    // SAFETY: Caller must ensure that buffers are not invalidated within the 'a lifetime, and that align is a power of two.
+    unsafe fn scan_bytes_with_align<'a>(&self, value: &[u8], align: usize) -> Option<&'a [u8]> {
+        let finder = Finder::new(value);
+        let buffers = self.full_buffers.iter().map(|buf| &**buf);
+        let buffers = buffers.chain(self.nonfull_buffers.iter().map(|buf| &**buf));
+        for buf in buffers {
+            // SAFETY: align is a power of two.
+            if let Some(owned) = unsafe { find_aligned(&finder, buf, align) } {
                 // SAFETY: The data buffer will never be reallocated
                 let owned: &'static [u8] = unsafe { std::mem::transmute(owned) };
                 return Some(owned);
@@ -113,17 +492,39 @@ impl DataInternerInner {
     }
 
     // SAFETY: Caller must ensure that buffers are not invalidated within the 'a lifetime, and that align is a power of two.
-    pub(crate) unsafe fn add_bytes_with_align<'a>(
+    pub(crate) unsafe fn try_add_bytes_with_align<'a>(
         &mut self,
         value: &[u8],
         align: usize,
-    ) -> &'a [u8] {
-        for (i, nonfull_buffer) in self.nonfull_buffers.iter_mut().enumerate() {
+    ) -> Result<&'a [u8], InternerError> {
+        // SAFETY: Same safety requirements as this function
+        let owned = unsafe { self.store_bytes_with_align(value, align) }?;
+        self.record_entry(owned);
+        Ok(owned)
+    }
+
+    // SAFETY: Caller must ensure that buffers are not invalidated within the 'static lifetime, and that align is a power of two.
+    unsafe fn store_bytes_with_align(
+        &mut self,
+        value: &[u8],
+        align: usize,
+    ) -> Result<&'static [u8], InternerError> {
+        // Find the nonfull buffer with the least remaining capacity that fits value (after alignment).
+        let best_fit =
+            self.free_space
+                .range((value.len(), 0)..)
+                .copied()
+                .find(|&(remaining_capacity, i)| {
+                    let nonfull_buffer = &self.nonfull_buffers[i];
+                    let ptr = nonfull_buffer.as_ptr().wrapping_add(nonfull_buffer.len());
+                    // SAFETY: align is a power of 2.
+                    let offset = unsafe { align_offset(align, ptr) };
+                    offset <= remaining_capacity && remaining_capacity - offset >= value.len()
+                });
+        if let Some((remaining_capacity, i)) = best_fit {
             // Append to an existing nonfull buffer
-            let remaining_capacity = nonfull_buffer.capacity() - nonfull_buffer.len();
-            if remaining_capacity < value.len() {
-                continue;
-            }
+            self.free_space.remove(&(remaining_capacity, i));
+            let nonfull_buffer = &mut self.nonfull_buffers[i];
 
             let old_len = nonfull_buffer.len();
 
@@ -132,12 +533,6 @@ impl DataInternerInner {
 
             // SAFETY: align is a power of 2.
             let offset = unsafe { align_offset(align, ptr) };
-            if offset > remaining_capacity {
-                continue;
-            }
-            if remaining_capacity - offset < value.len() {
-                continue;
-            }
 
             let fill_len = offset;
             let fill_ptr = ptr;
@@ -149,6 +544,7 @@ impl DataInternerInner {
                 // * dst must be valid for writes of count * size_of::<T = u8>() bytes. -> ptr comes from a vec with enough remaining capacity
                 // * dst must be properly aligned. -> align_of::<u8>() == 1
                 std::ptr::write_bytes(fill_ptr, b'\n', fill_len);
+                self.padding_bytes += fill_len;
 
                 // SAFETY:
                 // * src must be valid for reads of count * size_of::<T = u8>() bytes. -> src comes from value with length count
@@ -165,31 +561,21 @@ impl DataInternerInner {
             // SAFETY: The data buffer will never be reallocated
             let owned: &'static [u8] = unsafe { std::mem::transmute(owned) };
 
-            if nonfull_buffer.len() == nonfull_buffer.capacity() {
-                // Move the buffer to full_buffers.
-                // SAFETY: Vec::into_boxed_slice does not reallocate it's storage IF the length == the capacity.
-                // from docs for std::vec::Vec "If len == capacity, (as is the case for the vec! macro), then a Vec<T> can be converted to and from a Box<[T]>
-                // without reallocating or moving the elements.
-                let newly_full_buffer = self.nonfull_buffers.swap_remove(i);
-                self.full_buffers.push(newly_full_buffer.into_boxed_slice());
-            }
-            return owned;
+            self.file_nonfull_buffer(i);
+            return Ok(owned);
         }
         // Add a new buffer
+        let capacity = self.new_buffer_capacity(value.len(), value.len() + align - 1)?;
+        let mut buffer = Vec::<u8>::new();
+        buffer.try_reserve_exact(capacity)?;
         if align == 1 {
-            let vec = if value.len() < 1024 {
-                let mut vec = Vec::with_capacity(1024);
-                // NOTE: extend_from_slice may reallocate, but that is fine here because this vec is not used anywhere else, and we don't care about alignment.
-                vec.extend_from_slice(value);
-                vec
-            } else {
-                Vec::from(value)
-            };
+            // NOTE: extend_from_slice will not reallocate, since the capacity is sufficient.
+            buffer.extend_from_slice(value);
             // SAFETY: Same safety requirements as this function
-            unsafe { self.add_owned_bytes(vec) }
+            Ok(unsafe { self.store_owned_bytes(buffer) })
         } else {
-            let mut buffer = Vec::<u8>::with_capacity((value.len() + align - 1).max(1024));
             let capacity = buffer.capacity();
+            self.track_buffer(&buffer);
 
             let ptr = buffer.as_mut_ptr();
 
@@ -211,6 +597,7 @@ impl DataInternerInner {
                 // * dst must be valid for writes of count * size_of::<T = u8>() bytes. -> ptr comes from a vec with enough remaining capacity
                 // * dst must be properly aligned. -> align_of::<u8>() == 1
                 std::ptr::write_bytes(fill_ptr, b'\n', fill_len);
+                self.padding_bytes += fill_len;
 
                 // SAFETY:
                 // * src must be valid for reads of count * size_of::<T = u8>() bytes. -> src comes from value with length count
@@ -227,34 +614,530 @@ impl DataInternerInner {
             // SAFETY: The data buffer will never be reallocated
             let owned: &'static [u8] = unsafe { std::mem::transmute(owned) };
 
-            if buffer.len() == buffer.capacity() {
-                // Move the buffer to full_buffers. (The buffer may be full if the maximum alignment fill offset was required)
-                // SAFETY: Vec::into_boxed_slice does not reallocate it's storage IF the length == the capacity.
-                // from docs for std::vec::Vec "If len == capacity, (as is the case for the vec! macro), then a Vec<T> can be converted to and from a Box<[T]>
-                // without reallocating or moving the elements.
-                self.full_buffers.push(buffer.into_boxed_slice());
-            } else {
-                // Move the buffer to nonfull_buffers.
-                // SAFETY: moving a vec does not reallocate it's storage.
-                self.nonfull_buffers.push(buffer);
-            }
-            owned
+            // Move the buffer to nonfull_buffers, then retire it if it is (nearly) full.
+            // (The buffer may be full if the maximum alignment fill offset was required)
+            // SAFETY: moving a vec does not reallocate it's storage.
+            self.nonfull_buffers.push(buffer);
+            self.file_nonfull_buffer(self.nonfull_buffers.len() - 1);
+            Ok(owned)
         }
     }
 
     // TODO: Future: Maybe check for prefixes at the end of nonfull buffers.
     // SAFETY: Caller must ensure that buffers are not invalidated within the 'a lifetime, and align is a power of 2.
-    #[cfg_attr(not(feature = "bytemuck"), allow(dead_code))]
     pub(crate) unsafe fn find_or_add_bytes_with_align<'a>(
         &mut self,
         value: &[u8],
         align: usize,
     ) -> &'a [u8] {
+        // SAFETY: Same safety requirements as this function
+        expect_added(unsafe { self.try_find_or_add_bytes_with_align(value, align) })
+    }
+
+    // SAFETY: Caller must ensure that buffers are not invalidated within the 'a lifetime, and align is a power of 2.
+    pub(crate) unsafe fn try_find_or_add_bytes_with_align<'a>(
+        &mut self,
+        value: &[u8],
+        align: usize,
+    ) -> Result<&'a [u8], InternerError> {
         // SAFETY: Same safety requirements as this function
         match unsafe { self.find_bytes_with_align(value, align) } {
-            Some(owned) => owned,
+            Some(owned) => {
+                self.record_deduplicated();
+                Ok(owned)
+            }
             // SAFETY: Same safety requirements as this function
-            None => unsafe { self.add_bytes_with_align(value, align) },
+            None => unsafe { self.try_add_bytes_with_align(value, align) },
+        }
+    }
+
+    /// Records the allocation of a buffer that is being added to this interner.
+    fn track_buffer(&mut self, buffer: &Vec<u8>) {
+        self.capacity += buffer.capacity();
+        self.buffer_ranges
+            .insert(buffer.as_ptr() as usize, buffer.capacity());
+    }
+
+    /// Returns `true` if `value` lies entirely within one of this interner's buffers. Empty slices are always owned.
+    pub(crate) fn owns(&self, value: &[u8]) -> bool {
+        if value.is_empty() {
+            return true;
+        }
+        let start = value.as_ptr() as usize;
+        match self.buffer_ranges.range(..=start).next_back() {
+            Some((&buffer_start, &capacity)) => start + value.len() <= buffer_start + capacity,
+            None => false,
+        }
+    }
+
+    /// Returns the symbol for `value` interned as `ty` if `value` lies within this interner's buffers,
+    /// creating a symbol that refers to `value` itself if there is none for equal data yet.
+    ///
+    /// SAFETY: `value` must be initialized data in this interner's buffers if it is within them, e.g. a reference that was returned by this interner,
+    /// and the caller must ensure that buffers are not invalidated while the symbol table refers to them.
+    pub(crate) unsafe fn owned_symbol(&mut self, value: &[u8], ty: SymbolType) -> Option<Symbol> {
+        if !self.owns(value) {
+            return None;
+        }
+        if let Some(symbol) = self.find_symbol(value, ty) {
+            return Some(symbol);
+        }
+        // SAFETY: value points into this interner's buffers, which will never be reallocated.
+        let owned: &'static [u8] = unsafe { std::mem::transmute(value) };
+        Some(self.push_symbol(owned, ty))
+    }
+
+    /// Returns the symbol for data equal to `value` interned as `ty`, if one has been created.
+    ///
+    /// This includes unbound symbols for `ty`'s layout and [`id_hash`](SymbolType::id_hash), but does not bind them.
+    pub(crate) fn find_symbol(&self, value: &[u8], ty: SymbolType) -> Option<Symbol> {
+        let bound = self
+            .symbol_index
+            .as_ref()
+            .and_then(|symbol_index| symbol_index.get(&ty.type_id)?.get(value).copied());
+        bound.or_else(|| {
+            self.unbound_symbol_index
+                .as_ref()?
+                .get(&ty.layout)?
+                .get(value)?
+                .iter()
+                .copied()
+                .find(|&symbol| self.unbound_resolves_as(symbol, ty))
+        })
+    }
+
+    /// Returns `true` if there are symbols that may be bound by [`bind_symbol`](Self::bind_symbol).
+    #[cfg(any(feature = "bytemuck", test))]
+    pub(crate) fn has_unbound_symbols(&self) -> bool {
+        self.unbound_symbol_index.is_some()
+    }
+
+    /// Binds an unbound symbol for data equal to `value` with `ty`'s layout to `ty`, returning it,
+    /// preferring one that has `ty`'s [`id_hash`](SymbolType::id_hash).
+    ///
+    /// The caller must ensure that `value` is valid data of type `ty`, so the symbol's data, which is equal, is too.
+    pub(crate) fn bind_symbol(&mut self, value: &[u8], ty: SymbolType) -> Option<Symbol> {
+        let unbound_symbol_index = self.unbound_symbol_index.as_mut()?;
+        let by_data = unbound_symbol_index.get_mut(&ty.layout)?;
+        let candidates = by_data.get_mut(value)?;
+        let position = candidates
+            .iter()
+            .position(|&symbol| match self.symbols[symbol.index()].1 {
+                SymbolBinding::Unbound { id_hash, .. } => id_hash == ty.id_hash(),
+                _ => false,
+            })
+            .unwrap_or(0);
+        let symbol = candidates.remove(position);
+        if candidates.is_empty() {
+            by_data.remove(value);
+            if by_data.is_empty() {
+                unbound_symbol_index.remove(&ty.layout);
+                if unbound_symbol_index.is_empty() {
+                    self.unbound_symbol_index = None;
+                }
+            }
+        }
+        let owned = self.symbols[symbol.index()].0;
+        self.symbols[symbol.index()].1 = SymbolBinding::Bound(ty);
+        self.symbol_index
+            .get_or_insert_with(HashMap::new)
+            .entry(ty.type_id)
+            .or_default()
+            .entry(owned)
+            .or_insert(symbol);
+        Some(symbol)
+    }
+
+    /// Returns the symbol for data equal to `value` interned as `ty`, finding or adding the data and creating a symbol if necessary.
+    ///
+    /// An unbound symbol for equal data with `ty`'s layout is bound to `ty` instead of creating a new one.
+    // SAFETY: Caller must ensure that buffers are not invalidated while the symbol table refers to them, and that `value` is valid data of type `ty`.
+    pub(crate) unsafe fn find_or_add_symbol(&mut self, value: &[u8], ty: SymbolType) -> Symbol {
+        if let Some(symbol) = self
+            .find_symbol(value, ty)
+            .or_else(|| self.bind_symbol(value, ty))
+        {
+            self.record_deduplicated();
+            return symbol;
+        }
+        let owned: &'static [u8] = if value.is_empty() {
+            &[]
+        } else {
+            // SAFETY: Same safety requirements as this function. Layout alignments are powers of 2.
+            unsafe { self.find_or_add_bytes_with_align(value, ty.layout.align()) }
+        };
+        self.push_symbol(owned, ty)
+    }
+
+    /// Creates a new symbol for `owned`, which must point into this interner's buffers.
+    fn push_symbol(&mut self, owned: &'static [u8], ty: SymbolType) -> Symbol {
+        let symbol = Symbol::from_index(self.symbols.len());
+        self.symbols.push((owned, SymbolBinding::Bound(ty)));
+        self.symbol_index
+            .get_or_insert_with(HashMap::new)
+            .entry(ty.type_id)
+            .or_default()
+            .insert(owned, symbol);
+        symbol
+    }
+
+    /// Creates a new symbol for `owned`, which must point into this interner's buffers, whose type is not known yet.
+    ///
+    /// Unbound symbols are only bound if `owned` is sufficiently aligned for their layout.
+    pub(crate) fn push_unbound_symbol(
+        &mut self,
+        owned: &'static [u8],
+        binding: SymbolBinding,
+    ) -> Symbol {
+        let symbol = Symbol::from_index(self.symbols.len());
+        self.symbols.push((owned, binding));
+        if let SymbolBinding::Unbound { layout, .. } = binding {
+            let aligned = owned.is_empty() || pointer_alignment(owned.as_ptr()) >= layout.align();
+            if aligned && layout.size() != 0 && owned.len().is_multiple_of(layout.size()) {
+                self.unbound_symbol_index
+                    .get_or_insert_with(HashMap::new)
+                    .entry(layout)
+                    .or_default()
+                    .entry(owned)
+                    .or_default()
+                    .push(symbol);
+            }
+        }
+        symbol
+    }
+
+    /// Returns `true` if `symbol` is unbound and resolves as `ty`.
+    fn unbound_resolves_as(&self, symbol: Symbol, ty: SymbolType) -> bool {
+        match self.symbols.get(symbol.index()) {
+            Some(&(owned, SymbolBinding::Unbound { layout, id_hash })) => {
+                layout == ty.layout
+                    && id_hash == ty.id_hash()
+                    && self
+                        .unbound_symbol_index
+                        .as_ref()
+                        .and_then(|index| index.get(&layout)?.get(owned))
+                        .is_some_and(|symbols| symbols.contains(&symbol))
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the data referred to by `symbol`, if it is a symbol in this interner for data interned as `ty`.
+    // SAFETY: Caller must ensure that buffers are not invalidated within the 'a lifetime.
+    pub(crate) unsafe fn resolve<'a>(&self, symbol: Symbol, ty: SymbolType) -> Option<&'a [u8]> {
+        match *self.symbols.get(symbol.index())? {
+            (owned, SymbolBinding::Bound(symbol_ty)) if symbol_ty.type_id == ty.type_id => {
+                Some(owned)
+            }
+            (owned, SymbolBinding::Unbound { .. }) if self.unbound_resolves_as(symbol, ty) => {
+                Some(owned)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns the first (possibly overlapping) occurrence of `finder`'s needle in `haystack` that is aligned to `align`.
+///
+/// SAFETY: align must be a power of two.
+pub(crate) unsafe fn find_aligned<'h>(
+    finder: &Finder<'_>,
+    haystack: &'h [u8],
+    align: usize,
+) -> Option<&'h [u8]> {
+    let len = finder.needle().len();
+    let mut start = 0;
+    while start <= haystack.len() {
+        let idx = start + finder.find(&haystack[start..])?;
+        let found = &haystack[idx..][..len];
+        // SAFETY: align is a power of two.
+        if unsafe { is_aligned_to(align, found.as_ptr()) } {
+            return Some(found);
+        }
+        start = idx + 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DataInternerInner, SymbolType};
+    use crate::{InternerConfig, InternerError, LookupMode};
+
+    #[test]
+    fn index_prefers_aligned_entries() {
+        let mut inner = DataInternerInner::with_config(
+            InternerConfig::new().lookup_mode(LookupMode::ExactEntry),
+        );
+        let value = [0x55u8; 16];
+        // SAFETY: inner outlives all references, and is not cleared.
+        unsafe {
+            // Start of a new buffer, which will be at least 2-aligned.
+            inner.try_add_bytes_with_align(b"!", 1).unwrap();
+            let unaligned = inner.try_add_bytes_with_align(&value, 1).unwrap();
+            assert_eq!(unaligned.as_ptr() as usize % 2, 1);
+            assert_eq!(inner.find_bytes(&value), Some(unaligned));
+            assert_eq!(inner.find_bytes_with_align(&value, 16), None);
+
+            let aligned = inner.try_add_bytes_with_align(&value, 16).unwrap();
+            assert_eq!(aligned.as_ptr() as usize % 16, 0);
+            assert_eq!(inner.find_bytes_with_align(&value, 16), Some(aligned));
+            assert_eq!(inner.find_bytes(&value), Some(aligned));
+
+            // Adding a less-aligned copy does not replace the better-aligned one.
+            inner.try_add_bytes_with_align(&value, 1).unwrap();
+            assert_eq!(inner.find_bytes_with_align(&value, 16), Some(aligned));
+            assert_eq!(inner.find_bytes(&value[1..]), None);
+        }
+    }
+
+    #[test]
+    fn substring_skips_misaligned_matches() {
+        let mut inner = DataInternerInner::with_config(
+            InternerConfig::new().lookup_mode(LookupMode::Substring),
+        );
+        // SAFETY: inner outlives all references, and is not cleared.
+        unsafe {
+            let entry = inner
+                .try_add_bytes_with_align(&[0x01, 0x55, 0x55, 0x55, 0x55], 8)
+                .unwrap();
+            // The first match (at offset 1) is misaligned, but the next one is not.
+            let found = inner.find_bytes_with_align(&[0x55; 2], 2).unwrap();
+            assert_eq!(found.as_ptr(), entry[2..].as_ptr());
+            assert_eq!(inner.find_bytes_with_align(&[0x55; 4], 2), None);
+            inner.try_add_bytes_with_align(b"abc", 1).unwrap();
+            assert_eq!(inner.find_bytes(b"bc"), Some(&b"bc"[..]));
+            assert_eq!(inner.find_bytes(&[0x55, b'a']), None);
+        }
+    }
+
+    #[test]
+    fn chunks_grow_geometrically() {
+        let config = InternerConfig::new()
+            .initial_chunk_size(16)
+            .growth_factor(2)
+            .max_chunk_size(64)
+            .large_object_threshold(100);
+        let mut inner = DataInternerInner::with_config(config);
+        // SAFETY: inner outlives all references, and is not cleared.
+        unsafe {
+            for _ in 0..5 {
+                // Fills the rest of the current chunk, so each iteration allocates a new chunk.
+                inner.try_add_bytes_with_align(&[0; 12], 1).unwrap();
+                inner.try_add_bytes_with_align(&[1; 12], 1).unwrap();
+            }
+            inner.try_add_bytes_with_align(&[2; 200], 1).unwrap();
+        }
+        let capacities: Vec<usize> = inner
+            .full_buffers
+            .iter()
+            .map(|buffer| buffer.len())
+            .chain(inner.nonfull_buffers.iter().map(Vec::capacity))
+            .collect();
+        // Vec::with_capacity is only guaranteed to allocate *at least* the requested capacity, but is exact in practice.
+        assert!(capacities.contains(&16), "{capacities:?}");
+        assert!(capacities.contains(&32), "{capacities:?}");
+        assert!(
+            capacities.iter().filter(|&&c| c == 64).count() >= 2,
+            "{capacities:?}"
+        );
+        assert!(
+            capacities.iter().all(|&c| c <= 64 || c == 200),
+            "{capacities:?}"
+        );
+        assert!(capacities.contains(&200), "{capacities:?}");
+    }
+
+    /// Interns 100,000 entries of pseudo-random lengths between 16 and 48 bytes, then returns
+    /// the number of nonfull buffers and the fraction of allocated capacity that is not data.
+    fn placement_stats(config: InternerConfig) -> (usize, f64) {
+        let mut inner = DataInternerInner::with_config(config);
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        let mut data_len = 0;
+        for _ in 0..100_000 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let len = (state % 33) as usize + 16;
+            // SAFETY: inner outlives all references, and is not cleared.
+            unsafe {
+                inner
+                    .try_add_bytes_with_align(&state.to_le_bytes().repeat(6)[..len], 1)
+                    .unwrap()
+            };
+            data_len += len;
+        }
+        let capacity: usize = inner
+            .full_buffers
+            .iter()
+            .map(|buffer| buffer.len())
+            .sum::<usize>()
+            + inner
+                .nonfull_buffers
+                .iter()
+                .map(Vec::capacity)
+                .sum::<usize>();
+        for &(remaining_capacity, i) in &inner.free_space {
+            let buffer = &inner.nonfull_buffers[i];
+            assert_eq!(remaining_capacity, buffer.capacity() - buffer.len());
+        }
+        assert_eq!(inner.free_space.len(), inner.nonfull_buffers.len());
+        let waste = (capacity - data_len) as f64 / capacity as f64;
+        (inner.nonfull_buffers.len(), waste)
+    }
+
+    #[test]
+    fn best_fit_limits_fragmentation() {
+        // Only retiring buffers when they are exactly full leaves a partially-filled buffer behind for every chunk,
+        // since no entry is short enough to fill the gap at the end.
+        let (exact_nonfull, exact_waste) =
+            placement_stats(InternerConfig::new().retire_threshold(0));
+        let (nonfull, waste) = placement_stats(InternerConfig::new());
+        assert!(nonfull < exact_nonfull, "{nonfull} < {exact_nonfull}");
+        assert!(exact_nonfull > 20, "{exact_nonfull} nonfull buffers");
+        assert!(nonfull <= 2, "{nonfull} nonfull buffers");
+        assert!(waste < 0.01, "{waste} of capacity wasted");
+        assert!(exact_waste < 0.01, "{exact_waste} of capacity wasted");
+    }
+
+    #[test]
+    fn stats_count_padding() {
+        let mut inner =
+            DataInternerInner::with_config(InternerConfig::new().initial_chunk_size(32));
+        // SAFETY: inner outlives all references, and is not cleared.
+        unsafe {
+            inner.try_add_bytes_with_align(b"!", 1).unwrap();
+            inner.try_add_bytes_with_align(&[0x55; 8], 8).unwrap();
+            inner.find_or_add_bytes_with_align(&[0x55; 8], 8);
+        }
+        let stats = inner.stats();
+        assert_eq!(stats.capacity, 32);
+        assert_eq!(stats.bytes_used - stats.padding_bytes, 9);
+        assert!(stats.padding_bytes >= 7);
+        assert_eq!(stats.entries_added, 2);
+        assert_eq!(stats.entries_deduplicated, 1);
+
+        // Retiring the buffer pads it to its capacity.
+        // SAFETY: inner outlives all references, and is not cleared.
+        unsafe { inner.try_add_bytes_with_align(&[0xAA; 10], 1).unwrap() };
+        let stats = inner.stats();
+        assert_eq!((stats.full_buffers, stats.nonfull_buffers), (1, 0));
+        assert_eq!(stats.bytes_used, 32);
+        assert_eq!(stats.padding_bytes, 32 - 19);
+    }
+
+    #[test]
+    fn budget_limits_capacity() {
+        let config = InternerConfig::new().initial_chunk_size(64).budget(100);
+        let mut inner = DataInternerInner::with_config(config);
+        // SAFETY: inner outlives all references, and is not cleared.
+        unsafe {
+            inner.try_add_bytes(&[1; 60]).unwrap();
+            // The second chunk would be 128 bytes, but is shrunk to fit the remaining budget.
+            inner.try_add_bytes(&[2; 30]).unwrap();
+            assert_eq!(inner.stats().capacity, 100);
+            assert_eq!(
+                inner.try_add_bytes(&[3; 20]),
+                Err(InternerError::BudgetExceeded {
+                    required: 20,
+                    available: 0
+                })
+            );
+            // Existing data can still be found.
+            assert_eq!(inner.try_find_or_add_bytes(&[2; 20]), Ok(&[2; 20][..]));
+            assert_eq!(
+                inner.try_add_owned_bytes(Vec::with_capacity(1)),
+                Err(InternerError::BudgetExceeded {
+                    required: 1,
+                    available: 0
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn symbols_are_per_type() {
+        let mut inner = DataInternerInner::new();
+        // SAFETY: inner outlives all references, and is not cleared.
+        unsafe {
+            inner.try_add_bytes(b"!").unwrap();
+            let str_symbol = inner.find_or_add_symbol(b"abcd", SymbolType::str());
+            let u32_symbol = inner.find_or_add_symbol(b"abcd", SymbolType::of::<u32>());
+            assert_ne!(str_symbol, u32_symbol);
+            assert_eq!(
+                inner.find_symbol(b"abcd", SymbolType::str()),
+                Some(str_symbol)
+            );
+            assert_eq!(
+                inner.find_symbol(b"abcd", SymbolType::of::<u32>()),
+                Some(u32_symbol)
+            );
+
+            let data = inner.resolve(u32_symbol, SymbolType::of::<u32>()).unwrap();
+            assert_eq!(data, b"abcd");
+            assert_eq!(data.as_ptr() as usize % 4, 0);
+            assert_eq!(inner.resolve(u32_symbol, SymbolType::str()), None);
+        }
+    }
+
+    #[test]
+    fn entries_track_every_add() {
+        let mut inner =
+            DataInternerInner::with_config(InternerConfig::new().initial_chunk_size(16));
+        // SAFETY: inner outlives all references, and is only cleared after they are no longer used.
+        unsafe {
+            inner.try_add_bytes(b"abc").unwrap();
+            inner.try_add_bytes_with_align(&[1; 8], 8).unwrap();
+            inner.try_find_or_add_bytes(b"abc").unwrap();
+            inner.try_add_owned_bytes(b"owned".to_vec()).unwrap();
+            inner.try_add_bytes(b"12").unwrap();
+            inner.try_add_bytes(&[b'x'; 40]).unwrap();
+            let entries: Vec<&[u8]> = (0..).map_while(|i| inner.entry(i)).collect();
+            assert_eq!(
+                entries,
+                [b"abc" as &[u8], &[1; 8], b"owned", b"12", &[b'x'; 40]]
+            );
+            assert_eq!(inner.len(), 5);
+            inner.clear();
+            assert_eq!(inner.entry(0), None);
+            assert_eq!(inner.len(), 0);
+        }
+    }
+
+    #[test]
+    fn owns_every_buffer() {
+        let mut inner =
+            DataInternerInner::with_config(InternerConfig::new().initial_chunk_size(16));
+        // SAFETY: inner outlives all references, and is only cleared after they are no longer used.
+        unsafe {
+            let small = inner.try_add_bytes(b"abc").unwrap();
+            let aligned = inner.try_add_bytes_with_align(&[1; 8], 8).unwrap();
+            let owned = inner.try_add_owned_bytes(b"owned".to_vec()).unwrap();
+            let large = inner.try_add_bytes(&[b'x'; 40]).unwrap();
+            for value in [small, aligned, owned, large] {
+                assert!(inner.owns(value));
+                assert!(inner.owns(&value[1..]));
+            }
+            assert!(inner.owns(&[]));
+            assert!(!inner.owns(b"abc"));
+            assert!(!inner.owns(&[b'x'; 40]));
+
+            let mut other = DataInternerInner::new();
+            let appended = other.try_add_bytes(b"appended").unwrap();
+            inner.append(other);
+            assert!(inner.owns(appended));
+
+            let symbol = inner.owned_symbol(&large[..4], SymbolType::str()).unwrap();
+            assert_eq!(
+                inner.owned_symbol(&large[..4], SymbolType::str()),
+                Some(symbol)
+            );
+            assert_eq!(inner.find_symbol(b"xxxx", SymbolType::str()), Some(symbol));
+            assert_eq!(inner.owned_symbol(b"xxxx", SymbolType::str()), None);
+
+            inner.clear();
+            assert!(inner.owns(small));
+            assert!(!inner.owns(b"abc"));
         }
     }
 }
@@ -0,0 +1,766 @@
+//! Reading and writing snapshots of a [`DataInternerInner`].
+//!
+//! The format is documented on [`unsync::DataInterner::write_snapshot`](crate::unsync::DataInterner::write_snapshot).
+//! The `read_*` functions are shared with [`FrozenInterner`](crate::FrozenInterner), which reads a snapshot in place.
+
+use std::{
+    alloc::Layout,
+    any::TypeId,
+    collections::BTreeMap,
+    io::{self, Read, Write},
+};
+
+use super::{DataInternerInner, SymbolBinding, SymbolType};
+use crate::{
+    config::{InternerConfig, LookupMode},
+    util::{align_offset, pointer_alignment},
+};
+
+/// Identifies a snapshot file.
+const MAGIC: [u8; 8] = *b"INTRNSNP";
+/// The version of the snapshot format written by [`DataInternerInner::write_snapshot`].
+const VERSION: u32 = 3;
+/// The oldest version of the snapshot format that can be read.
+///
+/// Version 1 snapshots have no padding before each buffer's data, so it is not aligned within the snapshot, and have no symbols.
+/// Version 2 snapshots record neither the location nor the type of symbols that were not interned as `str`.
+const MIN_VERSION: u32 = 1;
+/// Entries are restored with at most this alignment.
+pub(crate) const MAX_ALIGN: usize = 256;
+
+pub(crate) fn invalid_data(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn write_u64(writer: &mut impl Write, value: usize) -> io::Result<()> {
+    writer.write_all(&(value as u64).to_le_bytes())
+}
+
+pub(crate) fn read_u64(reader: &mut impl Read) -> io::Result<usize> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    usize::try_from(u64::from_le_bytes(bytes))
+        .map_err(|_| invalid_data("snapshot value does not fit in usize"))
+}
+
+fn read_u8(reader: &mut impl Read) -> io::Result<u8> {
+    let mut byte = [0];
+    reader.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+/// Counts the bytes written, so buffers can be padded to their alignment within the snapshot.
+struct CountingWriter<W> {
+    writer: W,
+    written: usize,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.writer.write(buf)?;
+        self.written += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// The parts of a snapshot's header that are not per-buffer.
+pub(crate) struct Header {
+    pub(crate) version: u32,
+    pub(crate) config: InternerConfig,
+    pub(crate) next_chunk_size: usize,
+    pub(crate) padding_bytes: usize,
+}
+
+/// Reads and validates the magic bytes, version, configuration, and statistics at the start of a snapshot.
+pub(crate) fn read_header(reader: &mut impl Read) -> io::Result<Header> {
+    let mut magic = [0; 8];
+    reader.read_exact(&mut magic)?;
+    if magic != MAGIC {
+        return Err(invalid_data("not an interner snapshot"));
+    }
+    let mut version = [0; 4];
+    reader.read_exact(&mut version)?;
+    let version = u32::from_le_bytes(version);
+    if !(MIN_VERSION..=VERSION).contains(&version) {
+        return Err(invalid_data("unsupported interner snapshot version"));
+    }
+
+    let lookup_mode = match read_u8(reader)? {
+        0 => LookupMode::ExactEntry,
+        1 => LookupMode::Substring,
+        2 => LookupMode::CrossEntrySubstring,
+        _ => return Err(invalid_data("invalid lookup mode in interner snapshot")),
+    };
+    let has_budget = match read_u8(reader)? {
+        0 => false,
+        1 => true,
+        _ => return Err(invalid_data("invalid budget in interner snapshot")),
+    };
+    let budget = read_u64(reader)?;
+    let config = InternerConfig {
+        lookup_mode,
+        initial_chunk_size: read_u64(reader)?,
+        growth_factor: read_u64(reader)?,
+        max_chunk_size: read_u64(reader)?,
+        large_object_threshold: read_u64(reader)?,
+        retire_threshold: read_u64(reader)?,
+        budget: has_budget.then_some(budget),
+    };
+    Ok(Header {
+        version,
+        config,
+        next_chunk_size: read_u64(reader)?,
+        padding_bytes: read_u64(reader)?,
+    })
+}
+
+/// The header of one buffer in a snapshot, which is followed by `padding` bytes and then `len` bytes of data.
+pub(crate) struct BufferHeader {
+    /// The alignment the buffer's data must be placed at, modulo which its address must be `misalignment`.
+    pub(crate) align: usize,
+    pub(crate) misalignment: usize,
+    pub(crate) len: usize,
+    pub(crate) padding: usize,
+}
+
+/// Reads and validates the header of one buffer in a snapshot of the given `version`.
+pub(crate) fn read_buffer_header(reader: &mut impl Read, version: u32) -> io::Result<BufferHeader> {
+    let header = BufferHeader {
+        align: read_u64(reader)?,
+        misalignment: read_u64(reader)?,
+        len: read_u64(reader)?,
+        padding: if version >= 2 { read_u64(reader)? } else { 0 },
+    };
+    if !header.align.is_power_of_two()
+        || header.align > MAX_ALIGN
+        || header.misalignment >= header.align
+        || header.padding >= header.align
+    {
+        return Err(invalid_data(
+            "invalid buffer alignment in interner snapshot",
+        ));
+    }
+    Ok(header)
+}
+
+/// Reads the location of an entry or symbol's data, and returns that data from `buffers`.
+fn read_data<'a>(reader: &mut impl Read, buffers: &[&'a [u8]]) -> io::Result<&'a [u8]> {
+    let buffer = read_u64(reader)?;
+    let offset = read_u64(reader)?;
+    let len = read_u64(reader)?;
+    if len == 0 {
+        return Ok(&[]);
+    }
+    buffers
+        .get(buffer)
+        .and_then(|buffer| buffer.get(offset..offset.checked_add(len)?))
+        .ok_or_else(|| invalid_data("invalid data location in interner snapshot"))
+}
+
+/// Reads one entry, which is never empty.
+pub(crate) fn read_entry<'a>(reader: &mut impl Read, buffers: &[&'a [u8]]) -> io::Result<&'a [u8]> {
+    let entry = read_data(reader, buffers)?;
+    if entry.is_empty() {
+        return Err(invalid_data("empty entry in interner snapshot"));
+    }
+    Ok(entry)
+}
+
+/// A symbol read from a snapshot.
+pub(crate) enum SnapshotSymbol<'a> {
+    /// Interned as a `str`.
+    Str(&'a str),
+    /// Interned as a type with `layout` and [`id_hash`](super::SymbolType::id_hash).
+    Typed {
+        data: &'a [u8],
+        layout: Layout,
+        id_hash: u64,
+    },
+    /// Interned as a type other than `str` in a version 2 snapshot, which does not record it.
+    Unknown,
+}
+
+/// Reads one symbol from a snapshot of the given `version`.
+pub(crate) fn read_symbol<'a>(
+    reader: &mut impl Read,
+    buffers: &[&'a [u8]],
+    version: u32,
+) -> io::Result<SnapshotSymbol<'a>> {
+    match read_u8(reader)? {
+        0 => {
+            let data = read_data(reader, buffers)?;
+            std::str::from_utf8(data)
+                .map(SnapshotSymbol::Str)
+                .map_err(|_| invalid_data("invalid UTF-8 in interner snapshot symbol"))
+        }
+        1 if version < 3 => Ok(SnapshotSymbol::Unknown),
+        1 => {
+            let data = read_data(reader, buffers)?;
+            let size = read_u64(reader)?;
+            let align = read_u64(reader)?;
+            let mut id_hash = [0; 8];
+            reader.read_exact(&mut id_hash)?;
+            match Layout::from_size_align(size, align) {
+                Ok(layout) if size != 0 => Ok(SnapshotSymbol::Typed {
+                    data,
+                    layout,
+                    id_hash: u64::from_le_bytes(id_hash),
+                }),
+                _ => Err(invalid_data("invalid symbol layout in interner snapshot")),
+            }
+        }
+        2 if version >= 3 => Ok(SnapshotSymbol::Unknown),
+        _ => Err(invalid_data("invalid symbol in interner snapshot")),
+    }
+}
+
+impl DataInternerInner {
+    /// Writes the configuration, buffers, entries, and symbols of this interner to `writer`.
+    pub(crate) fn write_snapshot(&self, writer: impl Write) -> io::Result<()> {
+        let writer = &mut CountingWriter { writer, written: 0 };
+        let buffers: Vec<&[u8]> = self
+            .full_buffers
+            .iter()
+            .map(|buffer| &**buffer)
+            .chain(self.nonfull_buffers.iter().map(|buffer| &**buffer))
+            .filter(|buffer| !buffer.is_empty())
+            .collect();
+        let starts: BTreeMap<usize, usize> = buffers
+            .iter()
+            .enumerate()
+            .map(|(i, buffer)| (buffer.as_ptr() as usize, i))
+            .collect();
+        // Returns the buffer index and offset of `data`.
+        let locate = |data: &[u8]| -> (usize, usize) {
+            if data.is_empty() {
+                return (0, 0);
+            }
+            let address = data.as_ptr() as usize;
+            let (&start, &i) = starts
+                .range(..=address)
+                .next_back()
+                .expect("interned data lies within buffers");
+            debug_assert!(address - start + data.len() <= buffers[i].len());
+            (i, address - start)
+        };
+
+        // Find the alignment each buffer must be restored with to keep its entries and typed symbols aligned.
+        let mut aligns = vec![1; buffers.len()];
+        let typed_symbols = self
+            .symbols
+            .iter()
+            .filter_map(|&(data, binding)| match binding {
+                SymbolBinding::Bound(ty) if ty.type_id != TypeId::of::<str>() => Some(data),
+                SymbolBinding::Unbound { .. } => Some(data),
+                _ => None,
+            });
+        for data in self.entries.iter().copied().chain(typed_symbols) {
+            if data.is_empty() {
+                continue;
+            }
+            let (i, _) = locate(data);
+            aligns[i] = aligns[i].max(pointer_alignment(data.as_ptr()).min(MAX_ALIGN));
+        }
+
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        let InternerConfig {
+            lookup_mode,
+            initial_chunk_size,
+            growth_factor,
+            max_chunk_size,
+            large_object_threshold,
+            retire_threshold,
+            budget,
+        } = self.config;
+        let lookup_mode: u8 = match lookup_mode {
+            LookupMode::ExactEntry => 0,
+            LookupMode::Substring => 1,
+            LookupMode::CrossEntrySubstring => 2,
+        };
+        writer.write_all(&[lookup_mode, budget.is_some() as u8])?;
+        for value in [
+            budget.unwrap_or(0),
+            initial_chunk_size,
+            growth_factor,
+            max_chunk_size,
+            large_object_threshold,
+            retire_threshold,
+            self.next_chunk_size,
+            self.padding_bytes,
+        ] {
+            write_u64(writer, value)?;
+        }
+
+        write_u64(writer, buffers.len())?;
+        for (buffer, align) in buffers.iter().zip(aligns) {
+            let misalignment = buffer.as_ptr() as usize % align;
+            // The buffer's data starts after its header (4 u64s) and padding.
+            let unpadded = writer.written + 32;
+            let padding = (misalignment + align - unpadded % align) % align;
+            for value in [align, misalignment, buffer.len(), padding] {
+                write_u64(writer, value)?;
+            }
+            writer.write_all(&[0; MAX_ALIGN][..padding])?;
+            writer.write_all(buffer)?;
+        }
+
+        write_u64(writer, self.entries.len())?;
+        for entry in &self.entries {
+            let (buffer, offset) = locate(entry);
+            for value in [buffer, offset, entry.len()] {
+                write_u64(writer, value)?;
+            }
+        }
+
+        write_u64(writer, self.symbols.len())?;
+        for &(data, binding) in &self.symbols {
+            let (layout, id_hash) = match binding {
+                SymbolBinding::Bound(ty) if ty.type_id == TypeId::of::<str>() => {
+                    writer.write_all(&[0])?;
+                    let (buffer, offset) = locate(data);
+                    for value in [buffer, offset, data.len()] {
+                        write_u64(writer, value)?;
+                    }
+                    continue;
+                }
+                SymbolBinding::Bound(ty) => (ty.layout, ty.id_hash()),
+                SymbolBinding::Unbound { layout, id_hash } => (layout, id_hash),
+                SymbolBinding::Unknown => {
+                    writer.write_all(&[2])?;
+                    continue;
+                }
+            };
+            writer.write_all(&[1])?;
+            let (buffer, offset) = locate(data);
+            for value in [buffer, offset, data.len(), layout.size(), layout.align()] {
+                write_u64(writer, value)?;
+            }
+            writer.write_all(&id_hash.to_le_bytes())?;
+        }
+        writer.flush()
+    }
+
+    /// Reads an interner written by [`write_snapshot`](Self::write_snapshot) from `reader`.
+    pub(crate) fn read_snapshot(mut reader: impl Read) -> io::Result<Self> {
+        let reader = &mut reader;
+        let header = read_header(reader)?;
+        let mut this = Self::with_config(header.config);
+        this.next_chunk_size = header.next_chunk_size;
+        this.padding_bytes = header.padding_bytes;
+
+        // The data of each buffer, which stays in place when buffers are moved to `full_buffers`.
+        let mut buffers: Vec<&'static [u8]> = vec![];
+        for _ in 0..read_u64(reader)? {
+            let BufferHeader {
+                align,
+                misalignment,
+                len,
+                padding,
+            } = read_buffer_header(reader, header.version)?;
+            reader.read_exact(&mut [0; MAX_ALIGN][..padding])?;
+            if len == 0 {
+                buffers.push(&[]);
+                continue;
+            }
+            let capacity = len
+                .checked_add(align - 1)
+                .ok_or_else(|| invalid_data("invalid buffer length in interner snapshot"))?;
+            let mut buffer = Vec::<u8>::new();
+            buffer
+                .try_reserve_exact(capacity)
+                .map_err(|_| io::Error::from(io::ErrorKind::OutOfMemory))?;
+            // Pad the start of the buffer so its data has the same address modulo `align` as it did when written.
+            // SAFETY: align is a power of 2.
+            let offset =
+                unsafe { align_offset(align, buffer.as_ptr().wrapping_add(align - misalignment)) };
+            buffer.resize(offset, b'\n');
+            this.padding_bytes += offset;
+            // NOTE: Vec::resize will not reallocate, since the capacity is sufficient.
+            buffer.resize(offset + len, 0);
+            reader.read_exact(&mut buffer[offset..])?;
+
+            let data: &[u8] = &buffer[offset..];
+            // SAFETY: The data buffer will never be reallocated
+            let data: &'static [u8] = unsafe { std::mem::transmute(data) };
+            buffers.push(data);
+            this.track_buffer(&buffer);
+            this.nonfull_buffers.push(buffer);
+            this.file_nonfull_buffer(this.nonfull_buffers.len() - 1);
+        }
+
+        for _ in 0..read_u64(reader)? {
+            let entry = read_entry(reader, &buffers)?;
+            this.record_entry(entry);
+        }
+
+        if header.version < 2 {
+            return Ok(this);
+        }
+        for _ in 0..read_u64(reader)? {
+            match read_symbol(reader, &buffers, header.version)? {
+                SnapshotSymbol::Str(data) => {
+                    this.push_symbol(data.as_bytes(), SymbolType::str());
+                }
+                SnapshotSymbol::Typed {
+                    data,
+                    layout,
+                    id_hash,
+                } => {
+                    this.push_unbound_symbol(data, SymbolBinding::Unbound { layout, id_hash });
+                }
+                // Keep the numbering of later symbols, but never resolve this one.
+                SnapshotSymbol::Unknown => {
+                    this.push_unbound_symbol(&[], SymbolBinding::Unknown);
+                }
+            }
+        }
+        Ok(this)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::{DataInternerInner, SymbolType, MAX_ALIGN};
+    use crate::{symbol::Symbol, util::pointer_alignment, InternerConfig, LookupMode};
+
+    fn round_trip(inner: &DataInternerInner) -> DataInternerInner {
+        let mut snapshot = vec![];
+        inner.write_snapshot(&mut snapshot).unwrap();
+        DataInternerInner::read_snapshot(&snapshot[..]).unwrap()
+    }
+
+    #[test]
+    fn round_trip_preserves_entries_and_alignment() {
+        for lookup_mode in [
+            LookupMode::ExactEntry,
+            LookupMode::Substring,
+            LookupMode::CrossEntrySubstring,
+        ] {
+            let config = InternerConfig::new()
+                .lookup_mode(lookup_mode)
+                .initial_chunk_size(64)
+                .budget(1 << 20);
+            let mut inner = DataInternerInner::with_config(config);
+            // SAFETY: inner outlives all references, and is never cleared.
+            unsafe {
+                inner.try_add_bytes(b"abc").unwrap();
+                inner.try_add_bytes_with_align(&[1; 16], 16).unwrap();
+                inner.try_add_bytes(b"hello").unwrap();
+                inner.try_add_bytes_with_align(&[2; 8], 8).unwrap();
+                inner.try_add_bytes_with_align(&[3; 32], 32).unwrap();
+                inner.try_add_owned_bytes(b"owned".to_vec()).unwrap();
+                inner.try_add_bytes(&[b'x'; 100]).unwrap();
+            }
+            let restored = round_trip(&inner);
+            assert_eq!(restored.config(), config);
+            assert_eq!(restored.len(), inner.len());
+            for i in 0..inner.len() {
+                // SAFETY: Neither interner is modified while the entries are used.
+                let (original, restored) = unsafe { (inner.entry(i), restored.entry(i)) };
+                let (original, restored) = (original.unwrap(), restored.unwrap());
+                assert_eq!(original, restored);
+                assert!(
+                    pointer_alignment(restored.as_ptr())
+                        >= pointer_alignment(original.as_ptr()).min(MAX_ALIGN)
+                );
+            }
+            // SAFETY: restored is not modified while the references are used.
+            unsafe {
+                assert_eq!(
+                    restored.find_bytes_with_align(&[3; 32], 32),
+                    Some(&[3; 32][..])
+                );
+                assert_eq!(restored.find_bytes(b"hello"), Some(&b"hello"[..]));
+            }
+            assert_eq!(restored.stats().entries_added, inner.len());
+        }
+    }
+
+    #[test]
+    fn restored_interner_accepts_new_data() {
+        let mut inner = DataInternerInner::new();
+        // SAFETY: inner outlives all references, and is never cleared.
+        unsafe {
+            inner.try_add_bytes(b"abc").unwrap();
+        }
+        let mut restored = round_trip(&inner);
+        // SAFETY: restored outlives all references, and is never cleared.
+        unsafe {
+            let added = restored.try_add_bytes(b"def").unwrap();
+            assert!(restored.owns(added));
+            assert_eq!(restored.find_bytes(b"abc"), Some(&b"abc"[..]));
+        }
+        assert_eq!(round_trip(&restored).len(), 2);
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn round_trip_aligned_slices() {
+        use crate::{sync, unsync};
+
+        let interner = unsync::DataInterner::new();
+        interner.add_str("abc");
+        interner.add_slice::<u64>(&[1, 2, 3]);
+        interner.add_str("d");
+        interner.add_slice::<u128>(&[4, 5]);
+        interner.add_slice::<u16>(&[6, 7, 8]);
+        let mut snapshot = vec![];
+        interner.write_snapshot(&mut snapshot).unwrap();
+
+        let restored = sync::DataInterner::read_snapshot(&snapshot[..]).unwrap();
+        assert_eq!(restored.len(), 5);
+        assert_eq!(restored.find_slice::<u64>(&[1, 2, 3]), Some(&[1, 2, 3][..]));
+        assert_eq!(restored.find_slice::<u128>(&[4, 5]), Some(&[4, 5][..]));
+        assert_eq!(restored.find_slice::<u16>(&[7, 8]), Some(&[7, 8][..]));
+        let stats = restored.stats();
+        let u128s = restored.find_slice::<u128>(&[4, 5]).unwrap();
+        assert!(restored.owns(bytemuck::cast_slice(u128s)));
+
+        let mut resnapshot = vec![];
+        restored.write_snapshot(&mut resnapshot).unwrap();
+        let restored = unsync::DataInterner::read_snapshot(&resnapshot[..]).unwrap();
+        assert_eq!(
+            restored.iter_bytes().collect::<Vec<_>>(),
+            interner.iter_bytes().collect::<Vec<_>>()
+        );
+        assert_eq!(restored.stats().entries_added, stats.entries_added);
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn typed_symbols_round_trip() {
+        use crate::{sync, unsync};
+
+        let interner = unsync::DataInterner::new();
+        interner.add_str("!");
+        let numbers = interner.intern_slice::<u64>(&[1, 2, 3]);
+        let point = interner.intern_value(&[1.0f32, 2.0]);
+        let mut snapshot = vec![];
+        interner.write_snapshot(&mut snapshot).unwrap();
+
+        let restored = sync::DataInterner::read_snapshot(&snapshot[..]).unwrap();
+        assert_eq!(restored.resolve_slice(numbers), [1, 2, 3]);
+        assert_eq!(restored.resolve_value(point), &[1.0, 2.0]);
+        assert_eq!(restored.find_slice_symbol::<u64>(&[1, 2, 3]), Some(numbers));
+        assert_eq!(restored.intern_value(&[1.0f32, 2.0]), point);
+
+        let restored = unsync::DataInterner::read_snapshot(&snapshot[..]).unwrap();
+        assert_eq!(restored.intern_slice::<u64>(&[1, 2, 3]), numbers);
+        assert_eq!(restored.resolve_value(point), &[1.0, 2.0]);
+    }
+
+    #[test]
+    fn symbols_round_trip() {
+        let mut inner = DataInternerInner::new();
+        // SAFETY: inner outlives all references, and is never cleared.
+        unsafe {
+            let hello = inner.find_or_add_symbol(b"hello", SymbolType::str());
+            let number = inner.find_or_add_symbol(&[1, 2, 3, 4], SymbolType::of::<u32>());
+            let empty = inner.find_or_add_symbol(b"", SymbolType::str());
+            let ell = inner.find_or_add_symbol(b"ell", SymbolType::str());
+
+            let restored = round_trip(&inner);
+            assert_eq!(
+                restored.resolve(hello, SymbolType::str()),
+                Some(&b"hello"[..])
+            );
+            assert_eq!(restored.resolve(empty, SymbolType::str()), Some(&b""[..]));
+            assert_eq!(restored.resolve(ell, SymbolType::str()), Some(&b"ell"[..]));
+            assert_eq!(restored.find_symbol(b"ell", SymbolType::str()), Some(ell));
+
+            // Typed symbols resolve as the type they were interned as, identified by its layout and TypeId hash.
+            let data = restored.resolve(number, SymbolType::of::<u32>()).unwrap();
+            assert_eq!(data, [1, 2, 3, 4]);
+            assert_eq!(data.as_ptr() as usize % 4, 0);
+            assert_eq!(restored.resolve(number, SymbolType::of::<i32>()), None);
+            assert_eq!(restored.resolve(number, SymbolType::str()), None);
+            assert_eq!(
+                restored.find_symbol(&[1, 2, 3, 4], SymbolType::of::<u32>()),
+                Some(number)
+            );
+            assert_eq!(
+                restored.find_symbol(&[1, 2, 3, 4], SymbolType::of::<i32>()),
+                None
+            );
+            assert_eq!(
+                round_trip(&restored).resolve(number, SymbolType::of::<u32>()),
+                Some(&[1, 2, 3, 4][..])
+            );
+        }
+    }
+
+    #[test]
+    fn unbound_symbols_bind_to_the_first_type_with_their_layout() {
+        let mut inner = DataInternerInner::new();
+        // SAFETY: inner outlives all references, and is never cleared.
+        unsafe {
+            inner.try_add_bytes(b"!").unwrap();
+            let number = inner.find_or_add_symbol(&[1, 2, 3, 4], SymbolType::of::<u32>());
+            let wide = inner.find_or_add_symbol(&[5; 16], SymbolType::of::<u128>());
+            let mut restored = round_trip(&inner);
+
+            // A type with another TypeId, as if the snapshot were read by a different build.
+            assert_eq!(
+                restored.bind_symbol(&[1, 2, 3, 4], SymbolType::of::<u16>()),
+                None
+            );
+            assert_eq!(
+                restored.bind_symbol(&[1, 2, 3, 4], SymbolType::of::<i32>()),
+                Some(number)
+            );
+            assert_eq!(
+                restored.resolve(number, SymbolType::of::<i32>()),
+                Some(&[1, 2, 3, 4][..])
+            );
+            assert_eq!(restored.resolve(number, SymbolType::of::<u32>()), None);
+            assert_eq!(
+                restored.find_or_add_symbol(&[1, 2, 3, 4], SymbolType::of::<i32>()),
+                number
+            );
+            assert_ne!(
+                restored.find_or_add_symbol(&[1, 2, 3, 4], SymbolType::of::<u32>()),
+                number
+            );
+
+            assert_eq!(
+                restored.find_or_add_symbol(&[5; 16], SymbolType::of::<i128>()),
+                wide
+            );
+            assert!(!restored.has_unbound_symbols());
+            let wide_data = restored.resolve(wide, SymbolType::of::<i128>()).unwrap();
+            assert!(pointer_alignment(wide_data.as_ptr()) >= std::mem::align_of::<i128>());
+        }
+    }
+
+    #[test]
+    fn empty_round_trip() {
+        let restored = round_trip(&DataInternerInner::new());
+        assert_eq!(restored.len(), 0);
+        assert_eq!(restored.config(), InternerConfig::new());
+    }
+
+    fn read_error(snapshot: &[u8]) -> io::ErrorKind {
+        match DataInternerInner::read_snapshot(snapshot) {
+            Ok(_) => panic!("snapshot should be invalid"),
+            Err(error) => error.kind(),
+        }
+    }
+
+    #[test]
+    fn invalid_snapshots_are_rejected() {
+        let mut inner = DataInternerInner::new();
+        // SAFETY: inner outlives all references, and is never cleared.
+        unsafe {
+            inner.try_add_bytes(b"abc").unwrap();
+        }
+        let mut snapshot = vec![];
+        inner.write_snapshot(&mut snapshot).unwrap();
+
+        assert_eq!(
+            read_error(&snapshot[..snapshot.len() - 1]),
+            io::ErrorKind::UnexpectedEof
+        );
+
+        let mut bad_magic = snapshot.clone();
+        bad_magic[0] = b'X';
+        assert_eq!(read_error(&bad_magic[..]), io::ErrorKind::InvalidData);
+
+        let mut bad_version = snapshot.clone();
+        bad_version[8] = 4;
+        assert_eq!(read_error(&bad_version[..]), io::ErrorKind::InvalidData);
+
+        // The last entry's length is followed by the number of symbols.
+        let mut bad_entry = snapshot.clone();
+        let len = bad_entry.len();
+        bad_entry[len - 16] = 100;
+        assert_eq!(read_error(&bad_entry[..]), io::ErrorKind::InvalidData);
+    }
+
+    /// Written by the first version of this crate with snapshots, from an `unsync::DataInterner` with an initial chunk size of 64,
+    /// to which `"Hello, world!"`, the `u64`s `[1, 2, 3]`, `"Lorem ipsum"`, and 100 `b'x'`s were added.
+    const V1_SNAPSHOT: &[u8] = include_bytes!("../../tests/fixtures/snapshot_v1.bin");
+
+    #[test]
+    fn version_1_snapshots_are_read() {
+        assert_eq!(V1_SNAPSHOT[8..12], 1u32.to_le_bytes());
+        let restored = DataInternerInner::read_snapshot(V1_SNAPSHOT).unwrap();
+        assert_eq!(
+            restored.config(),
+            InternerConfig::new().initial_chunk_size(64)
+        );
+        assert_eq!(restored.len(), 4);
+        // SAFETY: restored is not modified while the references are used.
+        unsafe {
+            assert_eq!(restored.entry(0), Some(&b"Hello, world!"[..]));
+            assert_eq!(restored.entry(2), Some(&b"Lorem ipsum"[..]));
+            assert_eq!(restored.entry(3), Some(&[b'x'; 100][..]));
+            let u64s = restored.entry(1).unwrap();
+            assert_eq!(u64s, le_bytes(&[1, 2, 3]));
+            assert!(pointer_alignment(u64s.as_ptr()) >= 8);
+            assert_eq!(
+                restored.find_bytes_with_align(&le_bytes(&[1, 2, 3]), 8),
+                Some(u64s)
+            );
+            assert_eq!(restored.find_bytes(b"world"), Some(&b"world"[..]));
+        }
+        assert_eq!(restored.symbols.len(), 0);
+
+        // Writing it again upgrades it to the current version.
+        let upgraded = round_trip(&restored);
+        assert_eq!(upgraded.len(), 4);
+        let mut snapshot = vec![];
+        upgraded.write_snapshot(&mut snapshot).unwrap();
+        assert_eq!(snapshot[8..12], super::VERSION.to_le_bytes());
+    }
+
+    /// Written by the last version of this crate with version 2 snapshots, from an `unsync::DataInterner` with an initial chunk size of 64,
+    /// to which `"Hello, world!"` was added, then `"Lorem"`, the `u64`s `[1, 2, 3]`, and `"ipsum"` were interned.
+    const V2_SNAPSHOT: &[u8] = include_bytes!("../../tests/fixtures/snapshot_v2.bin");
+
+    #[test]
+    fn version_2_snapshots_are_read() {
+        assert_eq!(V2_SNAPSHOT[8..12], 2u32.to_le_bytes());
+        let restored = DataInternerInner::read_snapshot(V2_SNAPSHOT).unwrap();
+        let [lorem, numbers, ipsum] = [0, 1, 2].map(Symbol::from_index);
+        // SAFETY: restored is not modified while the references are used.
+        unsafe {
+            assert_eq!(restored.entry(0), Some(&b"Hello, world!"[..]));
+            assert_eq!(
+                restored.resolve(lorem, SymbolType::str()),
+                Some(&b"Lorem"[..])
+            );
+            assert_eq!(
+                restored.resolve(ipsum, SymbolType::str()),
+                Some(&b"ipsum"[..])
+            );
+            // Version 2 does not record where typed symbols' data is, so they never resolve.
+            assert_eq!(restored.resolve(numbers, SymbolType::of::<u64>()), None);
+            assert_eq!(
+                restored.find_symbol(&le_bytes(&[1, 2, 3]), SymbolType::of::<u64>()),
+                None
+            );
+
+            // Writing it again keeps the numbering of the symbols after it.
+            let upgraded = round_trip(&restored);
+            assert_eq!(upgraded.resolve(numbers, SymbolType::of::<u64>()), None);
+            assert_eq!(
+                upgraded.resolve(ipsum, SymbolType::str()),
+                Some(&b"ipsum"[..])
+            );
+        }
+    }
+
+    fn le_bytes(values: &[u64]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+}
@@ -0,0 +1,288 @@
+use std::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+    ptr::NonNull,
+};
+
+#[cfg(feature = "bytemuck")]
+use bytemuck::NoUninit;
+use stable_deref_trait::StableDeref;
+
+use crate::Interner;
+
+/// An owned handle to data interned in an [`Interner`], which keeps the interner alive through `R`,
+/// e.g. `Interned<str, Arc<sync::DataInterner>>` or `Interned<[u32], Rc<unsync::DataInterner>>`.
+///
+/// An `Interned` has no lifetime, so it can be stored in structs, and dereferences to the interned data.
+/// Comparisons, hashing, and formatting use the interned data, like they would for a `&T`.
+///
+/// This is the same idea as a [`RcInterner`](crate::RcInterner) `Yoke`, but does not need the `yoke` dependency.
+///
+/// # Example
+/// ```rust
+/// use std::{collections::HashSet, sync::Arc};
+/// use interner::{Interned, sync::DataInterner};
+/// struct Item {
+///     name: Interned<str, Arc<DataInterner>>,
+/// }
+/// let interner = Arc::new(DataInterner::new());
+/// let items: Vec<Item> = ["apple", "pear", "apple"]
+///     .into_iter()
+///     .map(|name| Item { name: Interned::find_or_add_str(&interner, name) })
+///     .collect();
+/// drop(interner);
+/// assert_eq!(&*items[0].name, "apple");
+/// assert_eq!(items[0].name, items[2].name);
+/// assert!(Interned::ptr_eq(&items[0].name, &items[2].name));
+/// assert_eq!(items.iter().map(|item| &item.name).collect::<HashSet<_>>().len(), 2);
+/// assert_eq!(format!("{} and {:?}", items[0].name, items[1].name), "apple and \"pear\"");
+/// ```
+pub struct Interned<T: ?Sized, R> {
+    /// SAFETY: Points to data owned by `*interner`, which remains valid for as long as `interner` exists,
+    /// since `R: StableDeref` and no `&mut` access to the interner is possible through `R` while it is shared.
+    data: NonNull<T>,
+    interner: R,
+}
+
+// SAFETY: An Interned<T, R> is equivalent to a (&T, R)
+unsafe impl<T: ?Sized + Sync, R: Send> Send for Interned<T, R> {}
+// SAFETY: An Interned<T, R> is equivalent to a (&T, R)
+unsafe impl<T: ?Sized + Sync, R: Sync> Sync for Interned<T, R> {}
+
+impl<T: ?Sized, R: StableDeref> Interned<T, R>
+where
+    R::Target: Interner,
+{
+    /// Creates a handle to the data returned by `f` when called with the interner.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::rc::Rc;
+    /// use interner::{Interned, Interner, unsync::DataInterner};
+    /// let interner = Rc::new(DataInterner::new());
+    /// let greeting = Interned::new(interner.clone(), |interner| interner.add_bytes(b"Hello"));
+    /// assert_eq!(&*greeting, b"Hello");
+    /// ```
+    pub fn new(interner: R, f: impl for<'a> FnOnce(&'a R::Target) -> &'a T) -> Self {
+        let data = NonNull::from(f(&*interner));
+        // SAFETY: data was borrowed from *interner, which does not move when interner is moved since R: StableDeref.
+        Self { data, interner }
+    }
+
+    /// Creates a handle to the data returned by `f` when called with the interner, if there is any.
+    pub fn try_new(
+        interner: R,
+        f: impl for<'a> FnOnce(&'a R::Target) -> Option<&'a T>,
+    ) -> Option<Self> {
+        let data = NonNull::from(f(&*interner)?);
+        // SAFETY: data was borrowed from *interner, which does not move when interner is moved since R: StableDeref.
+        Some(Self { data, interner })
+    }
+}
+
+impl<T: ?Sized, R> Interned<T, R> {
+    /// Returns the handle to the interner that `this` keeps alive.
+    ///
+    /// This is an associated function so that it does not shadow methods of `T`.
+    pub fn interner(this: &Self) -> &R {
+        &this.interner
+    }
+
+    /// Returns `true` if `this` and `other` refer to the same interned data (not just equal data).
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        std::ptr::eq(this.data.as_ptr(), other.data.as_ptr())
+    }
+}
+
+impl<R: StableDeref + Clone> Interned<str, R>
+where
+    R::Target: Interner,
+{
+    /// Returns a handle to a string equal to `value` in `interner`, if it exists. See [`Interner::find_str`].
+    pub fn find_str(interner: &R, value: &str) -> Option<Self> {
+        Self::try_new(interner.clone(), |interner| interner.find_str(value))
+    }
+
+    /// Returns a handle to a string equal to `value` in `interner`, adding it if it does not yet exist. See [`Interner::find_or_add_str`].
+    pub fn find_or_add_str(interner: &R, value: &str) -> Self {
+        Self::new(interner.clone(), |interner| interner.find_or_add_str(value))
+    }
+
+    /// Adds `value` to `interner`, returning a handle to it. See [`Interner::add_str`].
+    pub fn add_str(interner: &R, value: &str) -> Self {
+        Self::new(interner.clone(), |interner| interner.add_str(value))
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytemuck")))]
+impl<T: NoUninit + 'static, R: StableDeref + Clone> Interned<[T], R>
+where
+    R::Target: Interner,
+{
+    /// Returns a handle to data bytewise-equal to `value` in `interner`, if it exists and is sufficiently aligned. See [`Interner::find_slice`].
+    pub fn find_slice(interner: &R, value: &[T]) -> Option<Self> {
+        Self::try_new(interner.clone(), |interner| interner.find_slice(value))
+    }
+
+    /// Returns a handle to data bytewise-equal to `value` in `interner`, adding it if it does not yet exist or is not sufficiently aligned.
+    /// See [`Interner::find_or_add_slice`].
+    pub fn find_or_add_slice(interner: &R, value: &[T]) -> Self {
+        Self::new(interner.clone(), |interner| {
+            interner.find_or_add_slice(value)
+        })
+    }
+
+    /// Adds data bytewise-equal to `value` to `interner`, returning a handle to it. See [`Interner::add_slice`].
+    pub fn add_slice(interner: &R, value: &[T]) -> Self {
+        Self::new(interner.clone(), |interner| interner.add_slice(value))
+    }
+}
+
+impl<T: ?Sized, R> Deref for Interned<T, R> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: see the `data` field.
+        unsafe { self.data.as_ref() }
+    }
+}
+
+impl<T: ?Sized, R> AsRef<T> for Interned<T, R> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<T: ?Sized, R> Borrow<T> for Interned<T, R> {
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+impl<T: ?Sized, R: Clone> Clone for Interned<T, R> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data,
+            interner: self.interner.clone(),
+        }
+    }
+}
+
+impl<T: ?Sized + PartialEq, R> PartialEq for Interned<T, R> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: ?Sized + Eq, R> Eq for Interned<T, R> {}
+
+impl<T: ?Sized + PartialOrd, R> PartialOrd for Interned<T, R> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (**self).partial_cmp(&**other)
+    }
+}
+
+impl<T: ?Sized + Ord, R> Ord for Interned<T, R> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl<T: ?Sized + Hash, R> Hash for Interned<T, R> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl<T: ?Sized + fmt::Debug, R> fmt::Debug for Interned<T, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T: ?Sized + fmt::Display, R> fmt::Display for Interned<T, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::hash_map::DefaultHasher,
+        hash::{Hash, Hasher},
+        rc::Rc,
+        sync::Arc,
+    };
+
+    use super::Interned;
+    use crate::{sync, unsync};
+
+    fn hash(value: &(impl Hash + ?Sized)) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn keeps_interner_alive() {
+        let interner = Rc::new(unsync::DataInterner::new());
+        let hello = Interned::add_str(&interner, "Hello");
+        assert_eq!(
+            Interned::find_str(&interner, "Hell").as_deref(),
+            Some("Hell")
+        );
+        assert!(Interned::find_str(&interner, "Goodbye").is_none());
+        assert_eq!(Rc::strong_count(&interner), 2);
+        let weak = Rc::downgrade(&interner);
+        drop(interner);
+        let cloned = hello.clone();
+        assert_eq!(weak.strong_count(), 2);
+        drop(hello);
+        assert_eq!(&*cloned, "Hello");
+        drop(cloned);
+        assert_eq!(weak.strong_count(), 0);
+    }
+
+    #[test]
+    fn ordered_and_sendable() {
+        let interner = Arc::new(sync::DataInterner::new());
+        let mut names: Vec<_> = ["b", "c", "a", "b"]
+            .into_iter()
+            .map(|name| Interned::find_or_add_str(&interner, name))
+            .collect();
+        names.sort();
+        names.dedup();
+        let names = std::thread::spawn(move || names.into_iter().collect::<Vec<_>>())
+            .join()
+            .unwrap();
+        assert_eq!(
+            names,
+            ["a", "b", "c"].map(|name| Interned::find_str(&interner, name).unwrap())
+        );
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn slices() {
+        let interner = Rc::new(unsync::DataInterner::new());
+        let values = Interned::add_slice(&interner, &[1, 2, 3]);
+        assert_eq!(values.as_ptr() as usize % 4, 0);
+        let found = Interned::find_slice(&interner, &[2, 3]).unwrap();
+        assert_eq!(*found, [2, 3]);
+        assert!(found > values);
+        assert_eq!(found.cmp(&values), (*found).cmp(&*values));
+        assert_eq!(values.cmp(&found), (*values).cmp(&*found));
+
+        let copy = Interned::add_slice(&interner, &[1, 2, 3]);
+        assert!(!std::ptr::eq(&*copy, &*values));
+        assert_eq!(copy, values);
+        assert_eq!(hash(&copy), hash(&values));
+        assert_eq!(hash(&values), hash(&[1, 2, 3][..]));
+        assert_eq!(hash(&found), hash(&[2, 3][..]));
+    }
+}
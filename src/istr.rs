@@ -0,0 +1,165 @@
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+};
+
+/// A string interned in an [`Interner`](crate::Interner) that is compared and hashed by identity (address and length)
+/// instead of by content, so comparisons are O(1) regardless of the string's length.
+///
+/// Get one from [`Interner::find_or_add_istr`](crate::Interner::find_or_add_istr).
+///
+/// Comparing by identity gives the same result as comparing by content only if every `IStr` being compared is canonical,
+/// i.e. equal strings are always at the same address. This holds for `IStr`s from the same
+/// [`sync::DataInterner`](crate::sync::DataInterner) or [`unsync::DataInterner`](crate::unsync::DataInterner)
+/// (in any [`LookupMode`](crate::LookupMode)) until it is cleared, but not for `IStr`s from different interners,
+/// or `IStr`s created with [`IStr::new`] from strings that were not deduplicated.
+/// In debug builds, comparing `IStr`s asserts that the identity and content comparisons agree.
+///
+/// # Example
+/// ```rust
+/// use std::collections::HashMap;
+/// use interner::{IStr, Interner, unsync::DataInterner};
+/// let interner = DataInterner::new();
+/// let mut counts: HashMap<IStr<'_>, usize> = HashMap::new();
+/// for word in "the quick brown fox jumps over the lazy dog".split(' ') {
+///     *counts.entry(interner.find_or_add_istr(word)).or_default() += 1;
+/// }
+/// assert_eq!(counts[&interner.find_or_add_istr("the")], 2);
+/// assert_eq!(counts[&interner.find_or_add_istr("fox")], 1);
+/// ```
+#[derive(Clone, Copy)]
+pub struct IStr<'a>(&'a str);
+
+impl<'a> IStr<'a> {
+    /// Wraps `value`, which must be canonical (see the [type-level documentation](IStr)) for comparisons to be meaningful.
+    ///
+    /// Prefer [`Interner::find_or_add_istr`](crate::Interner::find_or_add_istr), which ensures this.
+    pub const fn new(value: &'a str) -> Self {
+        Self(value)
+    }
+
+    /// Returns the underlying string with the interner's lifetime `'a`, unlike [`Deref`](std::ops::Deref), which borrows from `self`.
+    pub const fn as_str(self) -> &'a str {
+        self.0
+    }
+
+    /// Returns `true` if `self` and `other` have the same address and length.
+    /// Empty strings always have the same identity.
+    fn same_identity(self, other: Self) -> bool {
+        self.0.len() == other.0.len() && (self.0.is_empty() || self.0.as_ptr() == other.0.as_ptr())
+    }
+}
+
+impl PartialEq for IStr<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        let same_identity = self.same_identity(*other);
+        debug_assert_eq!(
+            same_identity,
+            self.0 == other.0,
+            "IStrs {:?} and {:?} are not canonical, see the documentation of IStr",
+            self.0,
+            other.0,
+        );
+        same_identity
+    }
+}
+
+impl Eq for IStr<'_> {}
+
+impl Hash for IStr<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.len().hash(state);
+        if !self.0.is_empty() {
+            self.0.as_ptr().hash(state);
+        }
+    }
+}
+
+impl Deref for IStr<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.0
+    }
+}
+
+// NOTE: IStr does not implement Borrow<str>, since its Hash and Eq implementations are not consistent with str's.
+impl AsRef<str> for IStr<'_> {
+    fn as_ref(&self) -> &str {
+        self.0
+    }
+}
+
+impl<'a> From<IStr<'a>> for &'a str {
+    fn from(value: IStr<'a>) -> Self {
+        value.0
+    }
+}
+
+impl fmt::Debug for IStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.0, f)
+    }
+}
+
+impl fmt::Display for IStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.0, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::hash_map::DefaultHasher,
+        hash::{Hash, Hasher},
+    };
+
+    use super::IStr;
+    use crate::{sync, unsync, InternerConfig, LookupMode};
+
+    fn hash(value: IStr<'_>) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn canonical_in_every_lookup_mode() {
+        for lookup_mode in [
+            LookupMode::ExactEntry,
+            LookupMode::Substring,
+            LookupMode::CrossEntrySubstring,
+        ] {
+            let interner = sync::DataInterner::with_config(
+                InternerConfig::new()
+                    .lookup_mode(lookup_mode)
+                    .initial_chunk_size(32)
+                    .retire_threshold(8),
+            );
+            let first = interner.find_or_add_istr("abcd");
+            // Add more data, retiring buffers and adding an equal but more aligned entry.
+            for i in 0..100 {
+                interner.add_str(&format!("{i}abcd"));
+                #[cfg(feature = "bytemuck")]
+                interner.add_slice(&[u32::from_ne_bytes(*b"abcd")]);
+            }
+            let second = interner.find_or_add_istr(&String::from("abcd"));
+            assert_eq!(first, second);
+            assert_eq!(hash(first), hash(second));
+            assert_ne!(first, interner.find_or_add_istr("abc"));
+            assert_eq!(interner.find_or_add_istr(""), IStr::new(""));
+        }
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic = "are not canonical")]
+    fn different_interners_are_not_canonical() {
+        let interner1 = unsync::DataInterner::new();
+        let interner2 = unsync::DataInterner::new();
+        let a = interner1.find_or_add_istr("a");
+        let b = interner2.find_or_add_istr("a");
+        assert_ne!(a, b);
+    }
+}
@@ -0,0 +1,302 @@
+use std::fmt;
+
+use crate::{error::InternerError, istr::IStr, util::Interner};
+
+#[cfg(feature = "bytemuck")]
+use crate::error::TryAddOwnedError;
+#[cfg(feature = "bytemuck")]
+use bytemuck::NoUninit;
+
+/// An interner that looks up data in a read-only parent interner before a writable child interner, and only adds data to the child.
+///
+/// This lets many short-lived interners share a large, common set of data (e.g. a [`FrozenDataInterner`](crate::FrozenDataInterner)
+/// in an [`Arc`](std::sync::Arc), or a `&FrozenInterner`) while keeping their own additions separate.
+///
+/// `find_*` methods search the parent, then the child. `find_or_add_*` methods return data from the parent if it is found there,
+/// and otherwise find or add it in the child. `add_*` methods always add to the child, even if the parent contains equal data.
+/// [`try_clear`](Interner::try_clear) clears only the child, so references to the parent's data obtained from another handle to it
+/// (such as another clone of the `Arc`) remain valid.
+///
+/// # Example
+/// ```rust
+/// use interner::{Interner, LayeredInterner, unsync::DataInterner};
+/// use std::sync::Arc;
+///
+/// let std_symbols = DataInterner::new();
+/// std_symbols.add_str("Vec");
+/// let std_symbols = Arc::new(std_symbols.freeze());
+/// let vec = std_symbols.find_str("Vec").unwrap();
+///
+/// let mut session = LayeredInterner::new(Arc::clone(&std_symbols), DataInterner::new());
+/// assert_eq!(session.find_or_add_str("Vec").as_ptr(), vec.as_ptr());
+/// assert_eq!(session.find_or_add_str("MyType"), "MyType");
+/// assert_eq!(session.child().len(), 1);
+///
+/// session.try_clear().unwrap();
+/// assert_eq!(session.find_str("MyType"), None);
+/// assert_eq!(session.find_str("Vec"), Some("Vec"));
+/// assert_eq!(vec, "Vec");
+/// ```
+#[derive(Default)]
+pub struct LayeredInterner<P, C> {
+    parent: P,
+    child: C,
+}
+
+impl<P, C> LayeredInterner<P, C> {
+    /// Constructs an interner that looks up data in `parent`, then `child`, and adds data to `child`.
+    pub const fn new(parent: P, child: C) -> Self {
+        Self { parent, child }
+    }
+
+    /// Returns a reference to the parent interner.
+    pub fn parent(&self) -> &P {
+        &self.parent
+    }
+
+    /// Returns a reference to the child interner.
+    pub fn child(&self) -> &C {
+        &self.child
+    }
+
+    /// Returns a mutable reference to the child interner.
+    pub fn child_mut(&mut self) -> &mut C {
+        &mut self.child
+    }
+
+    /// Returns the parent and child interners.
+    pub fn into_parts(self) -> (P, C) {
+        (self.parent, self.child)
+    }
+}
+
+// SAFETY: Every reference returned is returned by the parent or the child, which are borrowed for as long as self is.
+unsafe impl<P: Interner, C: Interner> Interner for LayeredInterner<P, C> {
+    /// Clears the child interner. The parent is never cleared.
+    fn try_clear(&mut self) -> Result<(), InternerError> {
+        self.child.try_clear()
+    }
+
+    fn find_bytes(&self, value: &[u8]) -> Option<&[u8]> {
+        self.parent
+            .find_bytes(value)
+            .or_else(|| self.child.find_bytes(value))
+    }
+
+    fn owns(&self, value: &[u8]) -> bool {
+        self.parent.owns(value) || self.child.owns(value)
+    }
+
+    fn find_or_add_bytes(&self, value: &[u8]) -> &[u8] {
+        match self.parent.find_bytes(value) {
+            Some(found) => found,
+            None => self.child.find_or_add_bytes(value),
+        }
+    }
+
+    fn try_find_or_add_bytes(&self, value: &[u8]) -> Result<&[u8], InternerError> {
+        match self.parent.find_bytes(value) {
+            Some(found) => Ok(found),
+            None => self.child.try_find_or_add_bytes(value),
+        }
+    }
+
+    fn add_bytes(&self, value: &[u8]) -> &[u8] {
+        self.child.add_bytes(value)
+    }
+
+    fn try_add_bytes(&self, value: &[u8]) -> Result<&[u8], InternerError> {
+        self.child.try_add_bytes(value)
+    }
+
+    fn add_owned_bytes(&self, value: Vec<u8>) -> &[u8] {
+        self.child.add_owned_bytes(value)
+    }
+
+    fn add_owned_bytes_at(&self, value: Vec<u8>, start: usize) -> &[u8] {
+        self.child.add_owned_bytes_at(value, start)
+    }
+
+    fn add_owned_string(&self, value: String) -> &str {
+        self.child.add_owned_string(value)
+    }
+
+    /// The parent's data is never added to, so strings found in it are canonical, and other strings are canonical in the child.
+    fn find_or_add_istr(&self, value: &str) -> IStr<'_> {
+        match self.parent.find_str(value) {
+            Some(found) => IStr::new(found),
+            None => self.child.find_or_add_istr(value),
+        }
+    }
+
+    fn add_fmt(&self, args: fmt::Arguments<'_>) -> &str {
+        self.child.add_fmt(args)
+    }
+
+    #[cfg(feature = "bytemuck")]
+    fn find_slice<T: NoUninit + 'static>(&self, value: &[T]) -> Option<&[T]> {
+        self.parent
+            .find_slice(value)
+            .or_else(|| self.child.find_slice(value))
+    }
+
+    #[cfg(feature = "bytemuck")]
+    fn find_or_add_slice<T: NoUninit + 'static>(&self, value: &[T]) -> &[T] {
+        match self.parent.find_slice(value) {
+            Some(found) => found,
+            None => self.child.find_or_add_slice(value),
+        }
+    }
+
+    #[cfg(feature = "bytemuck")]
+    fn try_find_or_add_slice<T: NoUninit + 'static>(
+        &self,
+        value: &[T],
+    ) -> Result<&[T], InternerError> {
+        match self.parent.find_slice(value) {
+            Some(found) => Ok(found),
+            None => self.child.try_find_or_add_slice(value),
+        }
+    }
+
+    #[cfg(feature = "bytemuck")]
+    fn add_slice<T: NoUninit + 'static>(&self, value: &[T]) -> &[T] {
+        self.child.add_slice(value)
+    }
+
+    #[cfg(feature = "bytemuck")]
+    fn try_add_slice<T: NoUninit + 'static>(&self, value: &[T]) -> Result<&[T], InternerError> {
+        self.child.try_add_slice(value)
+    }
+
+    #[cfg(feature = "bytemuck")]
+    fn try_add_owned<T: NoUninit + 'static>(
+        &self,
+        value: Vec<T>,
+    ) -> Result<&[T], TryAddOwnedError<T>> {
+        self.child.try_add_owned(value)
+    }
+
+    #[cfg(feature = "bytemuck")]
+    fn find_value<T: NoUninit + 'static>(&self, value: &T) -> Option<&T> {
+        self.parent
+            .find_value(value)
+            .or_else(|| self.child.find_value(value))
+    }
+
+    #[cfg(feature = "bytemuck")]
+    fn find_or_add_value<T: NoUninit + 'static>(&self, value: &T) -> &T {
+        match self.parent.find_value(value) {
+            Some(found) => found,
+            None => self.child.find_or_add_value(value),
+        }
+    }
+
+    #[cfg(feature = "bytemuck")]
+    fn add_value<T: NoUninit + 'static>(&self, value: &T) -> &T {
+        self.child.add_value(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LayeredInterner;
+    use crate::{unsync::DataInterner, Interner, InternerConfig, InternerError};
+
+    fn parent() -> crate::FrozenDataInterner {
+        let parent = DataInterner::new();
+        parent.add_str("Hello, world!");
+        parent.add_str("Vec");
+        parent.freeze()
+    }
+
+    #[test]
+    fn lookups_prefer_the_parent() {
+        let parent = parent();
+        let layered = LayeredInterner::new(&parent, DataInterner::new());
+        let vec = layered.find_or_add_str("Vec");
+        assert!(parent.owns_str(vec));
+        assert!(layered.owns_str(vec));
+        assert_eq!(layered.find_str("world"), parent.find_str("world"));
+        assert_eq!(layered.child().len(), 0);
+
+        let string = layered.find_or_add_str("String");
+        assert!(layered.child().owns_str(string));
+        assert_eq!(layered.find_str("String"), Some(string));
+        assert_eq!(layered.find_or_add_str("String").as_ptr(), string.as_ptr());
+        assert_eq!(layered.child().len(), 1);
+
+        let added = layered.add_str("Vec");
+        assert!(layered.child().owns_str(added));
+        assert_eq!(layered.find_str("Vec").unwrap().as_ptr(), vec.as_ptr());
+        assert_eq!(layered.add_fmt(format_args!("{}", 42)), "42");
+        assert_eq!(layered.child().len(), 3);
+    }
+
+    #[test]
+    fn istrs_are_canonical() {
+        let parent = parent();
+        let layered = LayeredInterner::new(&parent, DataInterner::new());
+        let a = layered.find_or_add_istr("ab");
+        layered.add_str(&format!("{}ab0", "x".repeat(5000)));
+        let b = layered.find_or_add_istr("ab");
+        assert_eq!(a.as_str().as_ptr(), b.as_str().as_ptr());
+        assert_eq!(a, b);
+
+        let vec = layered.find_or_add_istr("Vec");
+        assert!(parent.owns_str(vec.as_str()));
+        layered.add_str("Vec");
+        assert_eq!(layered.find_or_add_istr("Vec"), vec);
+    }
+
+    #[test]
+    fn clearing_only_clears_the_child() {
+        let parent = parent();
+        let vec = parent.find_str("Vec").unwrap();
+        let mut layered = LayeredInterner::new(&parent, DataInterner::new());
+        layered.add_str("String");
+        layered.try_clear().unwrap();
+        assert_eq!(layered.find_str("String"), None);
+        assert_eq!(layered.find_str("Vec").unwrap().as_ptr(), vec.as_ptr());
+        assert_eq!(vec, "Vec");
+
+        let child = DataInterner::new();
+        let mut shared = LayeredInterner::new(&parent, &child);
+        assert_eq!(shared.try_clear(), Err(InternerError::Shared));
+        let (_, child) = shared.into_parts();
+        assert_eq!(child.len(), 0);
+    }
+
+    #[test]
+    fn child_errors_are_returned() {
+        let parent = parent();
+        let layered = LayeredInterner::new(
+            &parent,
+            DataInterner::with_config(InternerConfig::new().budget(16)),
+        );
+        assert_eq!(
+            layered.try_find_or_add_str("Hello, world!"),
+            Ok("Hello, world!")
+        );
+        assert!(matches!(
+            layered.try_add_str(&"x".repeat(100)),
+            Err(InternerError::BudgetExceeded { .. })
+        ));
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn slices_prefer_the_parent() {
+        let parent = DataInterner::new();
+        parent.add_slice::<u64>(&[1, 2, 3]);
+        let parent = parent.freeze();
+        let layered = LayeredInterner::new(&parent, DataInterner::new());
+        let found = layered.find_or_add_slice::<u64>(&[1, 2, 3]);
+        assert!(parent.owns(bytemuck::cast_slice(found)));
+        let value = layered.find_or_add_value(&4u32);
+        assert!(layered.child().owns(bytemuck::bytes_of(value)));
+        assert_eq!(layered.find_value(&4u32), Some(value));
+        assert_eq!(layered.try_add_owned(vec![5u8]), Ok(&[5u8] as &[u8]));
+        assert_eq!(layered.child().len(), 2);
+    }
+}
@@ -1,6 +1,7 @@
 #![cfg_attr(feature = "doc_cfg", feature(doc_cfg))]
 #![deny(unsafe_op_in_unsafe_fn)]
-//! `interner` provides two data interner types, [`unsync::DataInterner`] and [`sync::DataInterner`].
+//! `interner` provides two data interner types, [`unsync::DataInterner`] and [`sync::DataInterner`], plus [`sync::ShardedDataInterner`] for write-heavy multithreaded use,
+//! and [`FrozenInterner`] and [`FrozenDataInterner`] for read-only lookups in a snapshot without copying it, or in a frozen `DataInterner` without locking.
 //! A `DataInterner` can store byte slices, string slices, and (with the `bytemuck` feature enabled) slices and values of [`bytemuck::NoUninit`] types.
 //!
 //! The core API is essentially this:
@@ -9,38 +10,104 @@
 //! ```ignore
 //! impl sync::DataInterner {
 //!     pub /* #[cfg(feature = "parking_lot")] const */ fn new() -> Self;
+//!     pub fn with_lookup_mode(lookup_mode: LookupMode) -> Self;
+//!     pub fn with_config(config: InternerConfig) -> Self;
+//!     pub fn stats(&self) -> InternerStats;
+//!     pub fn len(&self) -> usize;
+//!     pub fn iter_bytes(&self) -> impl Iterator<Item = &[u8]>;
+//!     pub fn iter_strs(&self) -> impl Iterator<Item = &str>; // skips non-UTF-8 entries
 //!     pub fn into_unsync(self) -> unsync::DataInterner;
+//!     pub fn freeze(self) -> FrozenDataInterner;
+//!     pub fn write_snapshot(&self, writer: impl io::Write) -> io::Result<()>;
+//!     pub fn read_snapshot(reader: impl io::Read) -> io::Result<Self>;
 //!     pub fn clear(&mut self);
+//!     pub fn intern_str(&self, value: &str) -> Symbol;
+//!     pub fn resolve(&self, symbol: Symbol) -> &str;
+//!     pub fn to_symbol(&self, value: &str) -> Option<Symbol>; // None if value is not in this interner
 //! }
 //! impl unsync::DataInterner {
 //!     pub const fn new() -> Self;
+//!     pub fn with_lookup_mode(lookup_mode: LookupMode) -> Self;
+//!     pub fn with_config(config: InternerConfig) -> Self;
+//!     pub fn stats(&self) -> InternerStats;
+//!     pub fn len(&self) -> usize;
+//!     pub fn iter_bytes(&self) -> impl Iterator<Item = &[u8]>;
+//!     pub fn iter_strs(&self) -> impl Iterator<Item = &str>; // skips non-UTF-8 entries
 //!     pub fn into_sync(self) -> sync::DataInterner;
+//!     pub fn freeze(self) -> FrozenDataInterner;
+//!     pub fn write_snapshot(&self, writer: impl io::Write) -> io::Result<()>;
+//!     pub fn read_snapshot(reader: impl io::Read) -> io::Result<Self>;
 //!     pub fn clear(&mut self);
+//!     pub fn intern_str(&self, value: &str) -> Symbol;
+//!     pub fn resolve(&self, symbol: Symbol) -> &str;
+//!     pub fn to_symbol(&self, value: &str) -> Option<Symbol>; // None if value is not in this interner
 //! }
 //! trait Interner {
-//!     pub fn try_clear(&mut self) -> Result<(), ()>;
+//!     pub fn try_clear(&mut self) -> Result<(), InternerError>;
+//!
+//!     pub fn owns(&self, value: &[u8]) -> bool;
+//!     pub fn owns_str(&self, value: &str) -> bool;
 //!
 //!     pub fn find_bytes(&self, value: &[u8]) -> Option<&[u8]>;
 //!     pub fn find_or_add_bytes(&self, value: &[u8]) -> &[u8];
+//!     pub fn try_find_or_add_bytes(&self, value: &[u8]) -> Result<&[u8], InternerError>;
 //!     pub fn add_bytes(&self, value: &[u8]) -> &[u8];
+//!     pub fn try_add_bytes(&self, value: &[u8]) -> Result<&[u8], InternerError>;
 //!     pub fn add_owned_bytes(&self, value: Vec<u8>) -> &[u8];
 //!
 //!     pub fn find_str(&self, value: &str) -> Option<&str>;
 //!     pub fn find_or_add_str(&self, value: &str) -> &str;
+//!     pub fn try_find_or_add_str(&self, value: &str) -> Result<&str, InternerError>;
 //!     pub fn add_str(&self, value: &str) -> &str;
+//!     pub fn try_add_str(&self, value: &str) -> Result<&str, InternerError>;
 //!     pub fn add_owned_string(&self, value: String) -> &str;
+//!     pub fn find_or_add_istr(&self, value: &str) -> IStr<'_>; // compared by address, not content
+//!     pub fn add_fmt(&self, args: fmt::Arguments<'_>) -> &str; // also format_interned!(interner, ...)
+//!     pub fn find_or_add_fmt(&self, args: fmt::Arguments<'_>) -> &str;
 //! }
+//! // Also implemented for Rc<I>, Arc<I>, Box<I>, and &I.
+//! // The non-generic methods above are also available through the object-safe `DynInterner` trait.
 //! #[cfg(feature = "bytemuck")]
 //! trait Interner {
 //!     pub fn find_slice<T: NoUninit>(&self, value: &[T]) -> Option<&[T]>;
 //!     pub fn find_or_add_slice<T: NoUninit>(&self, value: &[T]) -> &[T];
+//!     pub fn try_find_or_add_slice<T: NoUninit>(&self, value: &[T]) -> Result<&[T], InternerError>;
 //!     pub fn add_slice<T: NoUninit>(&self, value: &[T]) -> &[T];
-//!     pub fn try_add_owned<T: NoUninit>(&self, value: Vec<T>) -> Result<&[T], Vec<T>>;
+//!     pub fn try_add_slice<T: NoUninit>(&self, value: &[T]) -> Result<&[T], InternerError>;
+//!     pub fn try_add_owned<T: NoUninit>(&self, value: Vec<T>) -> Result<&[T], TryAddOwnedError<T>>;
 //!
 //!     pub fn find_value<T: NoUninit>(&self, value: &T) -> Option<&T>;
 //!     pub fn find_or_add_value<T: NoUninit>(&self, value: &T) -> &T;
 //!     pub fn add_value<T: NoUninit>(&self, value: &T) -> &T;
 //! }
+//! impl<'a> FrozenInterner<'a> {
+//!     // A read-only interner over a borrowed snapshot (e.g. a memory-mapped file), which never copies interned data.
+//!     // Implements Interner, but try_* methods return Err(InternerError::Frozen) and other adds panic.
+//!     pub fn from_snapshot(snapshot: &'a [u8]) -> io::Result<Self>;
+//!     pub fn resolve(&self, symbol: Symbol) -> &'a str;
+//! }
+//! impl FrozenDataInterner {
+//!     // A read-only interner that owns a DataInterner's data, and is Send + Sync without any lock.
+//!     // Implements Interner like FrozenInterner, and also resolves TypedSymbols with the `bytemuck` feature.
+//!     pub fn resolve(&self, symbol: Symbol) -> &str;
+//! }
+//! impl<P: Interner, C: Interner> LayeredInterner<P, C> {
+//!     // Implements Interner: finds data in `parent` then `child`, only adds data to `child`, and try_clear only clears `child`.
+//!     pub const fn new(parent: P, child: C) -> Self;
+//! }
+//! #[cfg(feature = "interned")]
+//! impl<R: Clone + StableDeref<Target: Interner>> Interned<str, R> { // and find_slice etc. for Interned<[T], R> with the `bytemuck` feature
+//!     // An owned handle that keeps the interner alive and derefs to the data, without the `yoke` dependency.
+//!     pub fn find_str(interner: &R, value: &str) -> Option<Self>;
+//!     pub fn find_or_add_str(interner: &R, value: &str) -> Self;
+//!     pub fn add_str(interner: &R, value: &str) -> Self;
+//! }
+//! #[cfg(feature = "serde")]
+//! impl<'i, I: Interner + ?Sized> StrSeed<'i, I> { // and BytesSeed
+//!     // A DeserializeSeed<Value = &'i str> that interns deserialized strings into `interner`, e.g. to share storage between repeated keys.
+//!     pub fn new(interner: &'i I) -> Self;
+//! }
+//! // With the `serde` feature, DataInterners (as a snapshot), Symbols, and TypedSymbols also implement Serialize and Deserialize.
 //! #[cfg(feature = "yoke")]
 //! trait RcInterner: Clone + StableDeref + Deref<Target: Interner> {
 //!     // This trait is implemented for (e.g.) Rc<unsync::DataInterner> and Arc<sync::DataInterner>.
@@ -53,11 +120,39 @@
 
 #[cfg(feature = "builder")]
 pub mod builder;
+mod config;
+mod dyn_interner;
+mod error;
+mod frozen;
 pub(crate) mod inner;
+#[cfg(feature = "interned")]
+mod interned;
+mod istr;
+mod layered;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod stats;
+mod symbol;
 pub mod sync;
 pub mod unsync;
 
 mod util;
+pub use config::{InternerConfig, LookupMode};
+pub use dyn_interner::DynInterner;
+pub use error::InternerError;
+#[cfg(feature = "bytemuck")]
+pub use error::TryAddOwnedError;
+pub use frozen::{FrozenDataInterner, FrozenInterner};
+#[cfg(feature = "interned")]
+pub use interned::Interned;
+pub use istr::IStr;
+pub use layered::LayeredInterner;
+#[cfg(feature = "serde")]
+pub use serde_impls::{BytesSeed, StrSeed};
+pub use stats::InternerStats;
+pub use symbol::Symbol;
+#[cfg(feature = "bytemuck")]
+pub use symbol::TypedSymbol;
 pub use util::Interner;
 
 #[cfg(feature = "yoke")]
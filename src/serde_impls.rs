@@ -0,0 +1,429 @@
+use std::{fmt, io};
+
+use serde::{
+    de::{self, DeserializeSeed, Deserializer, SeqAccess, Visitor},
+    ser::{self, Serializer},
+    Deserialize, Serialize,
+};
+
+use crate::{sync, unsync, IStr, Interner, Symbol};
+
+#[cfg(feature = "interned")]
+use crate::Interned;
+#[cfg(feature = "bytemuck")]
+use crate::TypedSymbol;
+
+/// A [`DeserializeSeed`] that deserializes a string into an [`Interner`], returning a reference to the interned string.
+///
+/// Equal strings are deduplicated with [`Interner::try_find_or_add_str`], so e.g. keys that are repeated throughout a document share storage.
+/// If the string cannot be added, the error is returned as a deserialization error.
+///
+/// # Example
+/// ```rust
+/// use std::{collections::HashMap, fmt};
+/// use serde::de::{DeserializeSeed, Deserializer, MapAccess, Visitor};
+/// use interner::{StrSeed, unsync::DataInterner};
+///
+/// /// Deserializes a map, interning its keys.
+/// struct KeysSeed<'i>(&'i DataInterner);
+///
+/// impl<'de, 'i> DeserializeSeed<'de> for KeysSeed<'i> {
+///     type Value = HashMap<&'i str, u32>;
+///     fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+///         deserializer.deserialize_map(self)
+///     }
+/// }
+///
+/// impl<'de, 'i> Visitor<'de> for KeysSeed<'i> {
+///     type Value = HashMap<&'i str, u32>;
+///     fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         f.write_str("a map")
+///     }
+///     fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+///         let mut values = HashMap::new();
+///         while let Some(key) = map.next_key_seed(StrSeed::new(self.0))? {
+///             values.insert(key, map.next_value()?);
+///         }
+///         Ok(values)
+///     }
+/// }
+///
+/// let interner = DataInterner::new();
+/// let mut deserializer = serde_json::Deserializer::from_str(r#"{"width": 3, "height": 4}"#);
+/// let first = KeysSeed(&interner).deserialize(&mut deserializer).unwrap();
+/// let mut deserializer = serde_json::Deserializer::from_str(r#"{"height": 5}"#);
+/// let second = KeysSeed(&interner).deserialize(&mut deserializer).unwrap();
+/// let (first_key, _) = first.get_key_value("height").unwrap();
+/// let (second_key, _) = second.get_key_value("height").unwrap();
+/// assert!(std::ptr::eq(*first_key, *second_key));
+/// ```
+pub struct StrSeed<'i, I: ?Sized> {
+    interner: &'i I,
+}
+
+impl<'i, I: ?Sized> StrSeed<'i, I> {
+    /// Constructs a `StrSeed` that interns strings into `interner`.
+    pub fn new(interner: &'i I) -> Self {
+        Self { interner }
+    }
+}
+
+impl<I: ?Sized> Clone for StrSeed<'_, I> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I: ?Sized> Copy for StrSeed<'_, I> {}
+
+impl<'de, 'i, I: Interner + ?Sized> DeserializeSeed<'de> for StrSeed<'i, I> {
+    type Value = &'i str;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<&'i str, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de, 'i, I: Interner + ?Sized> Visitor<'de> for StrSeed<'i, I> {
+    type Value = &'i str;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a string")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<&'i str, E> {
+        self.interner.try_find_or_add_str(value).map_err(E::custom)
+    }
+}
+
+/// A [`DeserializeSeed`] that deserializes a byte string into an [`Interner`], returning a reference to the interned bytes.
+///
+/// Like [`StrSeed`], equal byte strings are deduplicated. Strings and sequences of bytes (as e.g. JSON represents byte strings) are also accepted.
+///
+/// # Example
+/// ```rust
+/// use serde::de::DeserializeSeed;
+/// use interner::{BytesSeed, Interner, sync::DataInterner};
+///
+/// let interner = DataInterner::new();
+/// let hello = interner.add_bytes(b"Hello, world!");
+/// let mut deserializer = serde_json::Deserializer::from_str("[72, 101, 108, 108, 111]");
+/// let bytes = BytesSeed::new(&interner).deserialize(&mut deserializer).unwrap();
+/// assert_eq!(bytes, b"Hello");
+/// assert!(std::ptr::eq(bytes, &hello[..5]));
+/// ```
+pub struct BytesSeed<'i, I: ?Sized> {
+    interner: &'i I,
+}
+
+impl<'i, I: ?Sized> BytesSeed<'i, I> {
+    /// Constructs a `BytesSeed` that interns byte strings into `interner`.
+    pub fn new(interner: &'i I) -> Self {
+        Self { interner }
+    }
+}
+
+impl<I: ?Sized> Clone for BytesSeed<'_, I> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I: ?Sized> Copy for BytesSeed<'_, I> {}
+
+impl<'de, 'i, I: Interner + ?Sized> DeserializeSeed<'de> for BytesSeed<'i, I> {
+    type Value = &'i [u8];
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<&'i [u8], D::Error> {
+        deserializer.deserialize_bytes(self)
+    }
+}
+
+impl<'de, 'i, I: Interner + ?Sized> Visitor<'de> for BytesSeed<'i, I> {
+    type Value = &'i [u8];
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a byte string")
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<&'i [u8], E> {
+        self.interner
+            .try_find_or_add_bytes(value)
+            .map_err(E::custom)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<&'i [u8], E> {
+        self.visit_bytes(value.as_bytes())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<&'i [u8], A::Error> {
+        self.visit_bytes(&collect_bytes(seq)?)
+    }
+}
+
+/// Collects a sequence of bytes.
+fn collect_bytes<'de, A: SeqAccess<'de>>(mut seq: A) -> Result<Vec<u8>, A::Error> {
+    let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+    while let Some(byte) = seq.next_element()? {
+        bytes.push(byte);
+    }
+    Ok(bytes)
+}
+
+/// Deserializes an interner from the bytes of a snapshot.
+struct SnapshotVisitor<T>(fn(&[u8]) -> io::Result<T>);
+
+impl<'de, T> Visitor<'de> for SnapshotVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an interner snapshot")
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<T, E> {
+        (self.0)(value).map_err(E::custom)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<T, A::Error> {
+        self.visit_bytes(&collect_bytes(seq)?)
+    }
+}
+
+/// Serializes a `DataInterner` as a byte string containing its snapshot.
+fn serialize_snapshot<S: Serializer>(
+    serializer: S,
+    write_snapshot: impl FnOnce(&mut Vec<u8>) -> io::Result<()>,
+) -> Result<S::Ok, S::Error> {
+    let mut snapshot = vec![];
+    write_snapshot(&mut snapshot).map_err(ser::Error::custom)?;
+    serializer.serialize_bytes(&snapshot)
+}
+
+/// An interner is serialized as a byte string containing its [snapshot](unsync::DataInterner::write_snapshot),
+/// so its lookup behavior and [`Symbol`]s are preserved.
+impl Serialize for unsync::DataInterner {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_snapshot(serializer, |snapshot| self.write_snapshot(snapshot))
+    }
+}
+
+/// An interner is deserialized from a byte string (or sequence of bytes) containing its [snapshot](unsync::DataInterner::read_snapshot).
+///
+/// # Example
+/// ```rust
+/// use interner::unsync::DataInterner;
+///
+/// let interner = DataInterner::new();
+/// let hello = interner.intern_str("Hello, world!");
+/// let json = serde_json::to_string(&interner).unwrap();
+/// let interner: DataInterner = serde_json::from_str(&json).unwrap();
+/// assert_eq!(interner.resolve(hello), "Hello, world!");
+/// ```
+impl<'de> Deserialize<'de> for unsync::DataInterner {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_bytes(SnapshotVisitor(|snapshot| Self::read_snapshot(snapshot)))
+    }
+}
+
+/// An interner is serialized as a byte string containing its [snapshot](sync::DataInterner::write_snapshot),
+/// so its lookup behavior and [`Symbol`]s are preserved.
+impl Serialize for sync::DataInterner {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_snapshot(serializer, |snapshot| self.write_snapshot(snapshot))
+    }
+}
+
+/// An interner is deserialized from a byte string (or sequence of bytes) containing its [snapshot](sync::DataInterner::read_snapshot).
+impl<'de> Deserialize<'de> for sync::DataInterner {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_bytes(SnapshotVisitor(|snapshot| Self::read_snapshot(snapshot)))
+    }
+}
+
+/// A symbol is serialized as its index in its interner's symbol table, as a `u32`.
+///
+/// # Example
+/// ```rust
+/// use interner::{Symbol, unsync::DataInterner};
+///
+/// let interner = DataInterner::new();
+/// let symbols = [interner.intern_str("Hello"), interner.intern_str("world")];
+/// let json = serde_json::to_string(&symbols).unwrap();
+/// assert_eq!(json, "[0,1]");
+/// let symbols: Vec<Symbol> = serde_json::from_str(&json).unwrap();
+/// assert_eq!(interner.resolve(symbols[1]), "world");
+/// ```
+impl Serialize for Symbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // NOTE: Symbol::from_index ensures that the index fits in a u32.
+        serializer.serialize_u32(self.index() as u32)
+    }
+}
+
+impl<'de> Deserialize<'de> for Symbol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let index = u32::deserialize(deserializer)?;
+        if index == u32::MAX {
+            return Err(de::Error::invalid_value(
+                de::Unexpected::Unsigned(index.into()),
+                &"a symbol index less than u32::MAX",
+            ));
+        }
+        Ok(Symbol::from_index(index as usize))
+    }
+}
+
+/// A typed symbol is serialized like a [`Symbol`].
+///
+/// It resolves in an interner deserialized from its interner's serialized data;
+/// see the [snapshot format](unsync::DataInterner::write_snapshot) for how its type is identified.
+#[cfg(feature = "bytemuck")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytemuck")))]
+impl<T> Serialize for TypedSymbol<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.symbol().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytemuck")))]
+impl<'de, T> Deserialize<'de> for TypedSymbol<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(TypedSymbol::new(Symbol::deserialize(deserializer)?))
+    }
+}
+
+/// An `IStr` is serialized as its string.
+impl Serialize for IStr<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// An `Interned` is serialized as its data.
+#[cfg(feature = "interned")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "interned")))]
+impl<T: Serialize + ?Sized, R> Serialize for Interned<T, R> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::de::DeserializeSeed;
+
+    use super::{BytesSeed, StrSeed};
+    use crate::{sync, unsync, InternerConfig, LookupMode, Symbol};
+
+    #[test]
+    fn seeds_deduplicate() {
+        let interner = unsync::DataInterner::with_lookup_mode(LookupMode::ExactEntry);
+        let mut deserializer = serde_json::Deserializer::from_str(r#" "key" "key" "other" "#);
+        let first = StrSeed::new(&interner)
+            .deserialize(&mut deserializer)
+            .unwrap();
+        let second = StrSeed::new(&interner)
+            .deserialize(&mut deserializer)
+            .unwrap();
+        let other = StrSeed::new(&interner)
+            .deserialize(&mut deserializer)
+            .unwrap();
+        assert_eq!((first, other), ("key", "other"));
+        assert!(std::ptr::eq(first, second));
+        assert_eq!(interner.len(), 2);
+
+        let mut deserializer = serde_json::Deserializer::from_str(r#" "key" [107, 101, 121] "#);
+        let from_str = BytesSeed::new(&interner)
+            .deserialize(&mut deserializer)
+            .unwrap();
+        let from_seq = BytesSeed::new(&interner)
+            .deserialize(&mut deserializer)
+            .unwrap();
+        assert!(std::ptr::eq(from_str, first.as_bytes()));
+        assert!(std::ptr::eq(from_seq, first.as_bytes()));
+    }
+
+    #[test]
+    fn seed_errors_are_deserialization_errors() {
+        let interner = sync::DataInterner::with_config(InternerConfig::new().budget(4));
+        let mut deserializer = serde_json::Deserializer::from_str(r#" "Hello, world!" 1 "#);
+        let error = StrSeed::new(&interner)
+            .deserialize(&mut deserializer)
+            .unwrap_err();
+        assert!(error.to_string().contains("budget"), "{error}");
+        let error = StrSeed::new(&interner)
+            .deserialize(&mut deserializer)
+            .unwrap_err();
+        assert!(error.is_data(), "{error}");
+    }
+
+    #[test]
+    fn interners_round_trip() {
+        let interner = sync::DataInterner::with_lookup_mode(LookupMode::Substring);
+        let hello = interner.intern_str("Hello, world!");
+        interner.add_str("Lorem ipsum");
+        let json = serde_json::to_string(&(&interner, hello)).unwrap();
+
+        let (restored, symbol): (unsync::DataInterner, Symbol) =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.lookup_mode(), LookupMode::Substring);
+        assert_eq!(restored.resolve(symbol), "Hello, world!");
+        assert_eq!(restored.find_str("ipsum"), Some("ipsum"));
+        assert_eq!(restored.find_str("!Lorem"), None);
+
+        let error = serde_json::from_str::<sync::DataInterner>("[1, 2, 3]")
+            .err()
+            .unwrap();
+        assert!(error.is_data(), "{error}");
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn typed_symbols_round_trip_with_their_interner() {
+        let interner = unsync::DataInterner::new();
+        interner.add_str("!");
+        let numbers = interner.intern_slice::<u64>(&[1, 2, 3]);
+        let point = interner.intern_value(&[0.5f32, 1.5]);
+        let json = serde_json::to_string(&(&interner, numbers, point)).unwrap();
+
+        type Restored = (
+            sync::DataInterner,
+            crate::TypedSymbol<u64>,
+            crate::TypedSymbol<[f32; 2]>,
+        );
+        let (restored, numbers, point): Restored = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.try_resolve_slice(numbers), Some(&[1, 2, 3][..]));
+        assert_eq!(restored.resolve_value(point), &[0.5, 1.5]);
+        assert_eq!(restored.find_slice_symbol::<u64>(&[1, 2, 3]), Some(numbers));
+        assert_eq!(restored.find_slice_symbol(&[[0.5f32, 1.5]]), Some(point));
+        assert_eq!(restored.intern_slice::<u64>(&[1, 2, 3]), numbers);
+    }
+
+    #[test]
+    fn invalid_symbols_are_rejected() {
+        assert_eq!(
+            serde_json::from_str::<Symbol>("4294967294").unwrap(),
+            Symbol::from_index(u32::MAX as usize - 1)
+        );
+        assert!(serde_json::from_str::<Symbol>("4294967295").is_err());
+        assert!(serde_json::from_str::<Symbol>("-1").is_err());
+    }
+
+    #[cfg(all(feature = "bytemuck", feature = "interned"))]
+    #[test]
+    fn typed_symbols_and_interned_data_serialize() {
+        let interner = std::rc::Rc::new(unsync::DataInterner::new());
+        let symbol = interner.intern_slice::<u32>(&[1, 2, 3]);
+        let json = serde_json::to_string(&symbol).unwrap();
+        assert_eq!(
+            serde_json::from_str::<crate::TypedSymbol<u32>>(&json).unwrap(),
+            symbol
+        );
+
+        let istr = interner.find_or_add_istr("istr");
+        assert_eq!(serde_json::to_string(&istr).unwrap(), r#""istr""#);
+        let slice = crate::Interned::find_or_add_slice(&interner, &[4u16, 5]);
+        assert_eq!(serde_json::to_string(&slice).unwrap(), "[4,5]");
+    }
+}
@@ -0,0 +1,49 @@
+use std::ops::AddAssign;
+
+/// Memory usage statistics for an interner, returned by e.g. [`sync::DataInterner::stats`](crate::sync::DataInterner::stats).
+///
+/// All sizes are in bytes.
+///
+/// # Example
+/// ```rust
+/// use interner::{Interner, unsync::DataInterner};
+/// let interner = DataInterner::new();
+/// interner.add_str("Hello, world!");
+/// interner.add_bytes(b"Lorem ipsum");
+/// interner.find_or_add_str("Hello, world!");
+/// let stats = interner.stats();
+/// assert_eq!(stats.entries_added, 2);
+/// assert_eq!(stats.entries_deduplicated, 1);
+/// assert_eq!(stats.bytes_used, 13 + 11);
+/// assert_eq!(stats.padding_bytes, 0);
+/// assert!(stats.capacity >= stats.bytes_used);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InternerStats {
+    /// The number of buffers that are not considered for new data.
+    pub full_buffers: usize,
+    /// The number of buffers that new data may be added to.
+    pub nonfull_buffers: usize,
+    /// The total capacity of all buffers.
+    pub capacity: usize,
+    /// The number of bytes of all buffers that are in use, including padding.
+    pub bytes_used: usize,
+    /// The number of bytes used for padding, either to align data or to fill the end of a retired buffer.
+    pub padding_bytes: usize,
+    /// The number of non-empty entries added.
+    pub entries_added: usize,
+    /// The number of times a `find_or_add_*` or `intern_*` method found existing data instead of adding it.
+    pub entries_deduplicated: usize,
+}
+
+impl AddAssign for InternerStats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.full_buffers += rhs.full_buffers;
+        self.nonfull_buffers += rhs.nonfull_buffers;
+        self.capacity += rhs.capacity;
+        self.bytes_used += rhs.bytes_used;
+        self.padding_bytes += rhs.padding_bytes;
+        self.entries_added += rhs.entries_added;
+        self.entries_deduplicated += rhs.entries_deduplicated;
+    }
+}
@@ -0,0 +1,174 @@
+use std::num::NonZeroU32;
+
+#[cfg(feature = "bytemuck")]
+use bytemuck::NoUninit;
+#[cfg(feature = "bytemuck")]
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+/// A compact handle to a string interned in a [`sync::DataInterner`](crate::sync::DataInterner) or [`unsync::DataInterner`](crate::unsync::DataInterner).
+///
+/// A `Symbol` is 4 bytes, is [`Copy`], and has no lifetime, so it can be stored anywhere.
+/// Use the interner's `resolve` method to get the interned string back.
+///
+/// Interning equal strings into the same interner gives equal symbols, so symbols from the same interner can be compared directly instead of comparing strings.
+///
+/// A `Symbol` is only meaningful for the interner that created it (and only until that interner is cleared).
+/// Resolving it with a different interner may return an unrelated string or panic, but is never unsafe.
+///
+/// # Example
+/// ```rust
+/// use interner::{Symbol, unsync::DataInterner};
+/// let interner = DataInterner::new();
+/// let hello: Symbol = interner.intern_str("Hello");
+/// let world: Symbol = interner.intern_str("world");
+/// assert_eq!(interner.intern_str("Hello"), hello);
+/// assert_ne!(hello, world);
+/// assert_eq!(interner.resolve(hello), "Hello");
+/// assert_eq!(std::mem::size_of::<Option<Symbol>>(), 4);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(NonZeroU32);
+
+impl Symbol {
+    /// Returns the symbol for the entry at `index` in an interner's symbol table.
+    ///
+    /// Panics if there are too many symbols to fit in a `u32`.
+    pub(crate) fn from_index(index: usize) -> Self {
+        u32::try_from(index)
+            .ok()
+            .and_then(|index| index.checked_add(1))
+            .and_then(NonZeroU32::new)
+            .map(Self)
+            .expect("too many symbols")
+    }
+
+    /// Returns the index of this symbol in its interner's symbol table.
+    pub(crate) fn index(self) -> usize {
+        (self.0.get() - 1) as usize
+    }
+}
+
+/// A compact handle to a slice of `T` interned in a [`sync::DataInterner`](crate::sync::DataInterner) or [`unsync::DataInterner`](crate::unsync::DataInterner).
+///
+/// Like [`Symbol`], a `TypedSymbol` is 4 bytes, is [`Copy`], and has no lifetime.
+/// Use the interner's `resolve_slice` or `resolve_value` methods to get the interned data back, correctly aligned for `T`.
+///
+/// A `TypedSymbol` is only meaningful for the interner that created it (and only until that interner is cleared).
+/// Resolving it with a different interner may return unrelated data of type `T` or panic, but is never unsafe.
+///
+/// # Example
+/// ```rust
+/// use interner::{TypedSymbol, unsync::DataInterner};
+///
+/// #[derive(Clone, Copy)]
+/// struct Triangle {
+///     vertices: [TypedSymbol<[f32; 3]>; 3],
+/// }
+///
+/// let interner = DataInterner::new();
+/// let origin = interner.intern_value(&[0.0f32; 3]);
+/// let triangle = Triangle {
+///     vertices: [origin, interner.intern_value(&[1.0, 0.0, 0.0]), interner.intern_value(&[0.0, 1.0, 0.0])],
+/// };
+/// assert_eq!(interner.intern_value(&[0.0f32; 3]), origin);
+/// assert_eq!(interner.resolve_value(triangle.vertices[1]), &[1.0, 0.0, 0.0]);
+/// ```
+#[cfg(feature = "bytemuck")]
+#[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytemuck")))]
+pub struct TypedSymbol<T> {
+    symbol: Symbol,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+#[cfg(feature = "bytemuck")]
+impl<T> TypedSymbol<T> {
+    pub(crate) fn new(symbol: Symbol) -> Self {
+        Self {
+            symbol,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub(crate) fn symbol(self) -> Symbol {
+        self.symbol
+    }
+}
+
+// Manual impls to avoid requiring `T: Trait`.
+
+#[cfg(feature = "bytemuck")]
+impl<T> Clone for TypedSymbol<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<T> Copy for TypedSymbol<T> {}
+
+#[cfg(feature = "bytemuck")]
+impl<T> PartialEq for TypedSymbol<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.symbol == other.symbol
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<T> Eq for TypedSymbol<T> {}
+
+#[cfg(feature = "bytemuck")]
+impl<T> PartialOrd for TypedSymbol<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<T> Ord for TypedSymbol<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.symbol.cmp(&other.symbol)
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<T> Hash for TypedSymbol<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.symbol.hash(state)
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<T> fmt::Debug for TypedSymbol<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "TypedSymbol<{}>({})",
+            std::any::type_name::<T>(),
+            self.symbol.0
+        )
+    }
+}
+
+/// Reinterprets the data of a typed symbol as a slice of `T`.
+///
+/// SAFETY: `data` must have been interned from a `[T]` with alignment `align_of::<T>()`, and `T` must not be zero-sized.
+#[cfg(feature = "bytemuck")]
+pub(crate) unsafe fn cast_symbol_data<T: NoUninit>(data: &[u8]) -> &[T] {
+    if data.is_empty() {
+        return &[];
+    }
+    let len = data.len() / std::mem::size_of::<T>();
+    // This would require T: AnyBitPattern, but that is more restrictive than necessary, since we know the bit pattern matches the original
+    // cast_slice(data)
+    unsafe {
+        let ptr = data.as_ptr();
+        let ptr = ptr as *const T;
+        // SAFETY: T is Copy has no interior mutability, and ptr points to equal bytes as the interned value did, with the same alignment.
+        std::slice::from_raw_parts(ptr, len)
+    }
+}
@@ -1,15 +1,33 @@
-use crate::{inner::DataInternerInner, unsync::DataInterner as UnSyncDataInterner, util::Interner};
-use std::cell::RefCell;
+use crate::{
+    config::{InternerConfig, LookupMode},
+    error::{expect_added, InternerError},
+    frozen::FrozenDataInterner,
+    inner::{DataInternerInner, SymbolType},
+    istr::IStr,
+    stats::InternerStats,
+    symbol::Symbol,
+    unsync::DataInterner as UnSyncDataInterner,
+    util::{format_with, make_inherent_impls, Interner},
+};
+use std::{cell::RefCell, fmt, io};
 
 #[cfg(not(feature = "parking_lot"))]
 pub(crate) use crate::util::RwLock;
 #[cfg(feature = "parking_lot")]
-pub(crate) use parking_lot::RwLock;
+pub(crate) use parking_lot::{RwLock, RwLockUpgradableReadGuard};
 
+#[cfg(feature = "bytemuck")]
+use crate::{
+    error::TryAddOwnedError,
+    symbol::{cast_symbol_data, TypedSymbol},
+};
 #[cfg(feature = "bytemuck")]
 use bytemuck::{cast_slice, try_cast_vec, NoUninit};
 #[cfg(feature = "bytemuck")]
-use std::{mem::size_of, ptr::NonNull};
+use std::{
+    mem::{align_of, size_of},
+    ptr::NonNull,
+};
 
 /// A thread-safe data interner.
 ///
@@ -54,6 +72,140 @@ impl DataInterner {
         }
     }
 
+    /// Constructs a new, empty `DataInterner` that matches lookups according to `lookup_mode`.
+    ///
+    /// [`new`](DataInterner::new) uses [`LookupMode::CrossEntrySubstring`].
+    ///
+    /// The interner will not allocate until something is added to it.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, LookupMode, sync::DataInterner};
+    /// let interner = DataInterner::with_lookup_mode(LookupMode::ExactEntry);
+    /// let greeting1 = interner.add_str("Hello, Mary Sue!");
+    /// let greeting2 = interner.find_str("Hello, Mary Sue!");
+    /// let greeting3 = interner.find_str("Hello, Mary");
+    /// assert_eq!(greeting2.map(str::as_ptr), Some(greeting1.as_ptr()));
+    /// assert_eq!(greeting3, None);
+    /// ```
+    pub fn with_lookup_mode(lookup_mode: LookupMode) -> Self {
+        Self::with_config(InternerConfig::new().lookup_mode(lookup_mode))
+    }
+
+    /// Constructs a new, empty `DataInterner` with the given configuration.
+    ///
+    /// The interner will not allocate until something is added to it.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, InternerConfig, sync::DataInterner};
+    /// // Many small chunks for an interner that will only hold a few short strings.
+    /// let config = InternerConfig::new().initial_chunk_size(64).max_chunk_size(256);
+    /// let interner = DataInterner::with_config(config);
+    /// assert_eq!(interner.config(), config);
+    /// let greeting = interner.add_str("Hello, world!");
+    /// assert_eq!(interner.find_str("world").map(str::as_ptr), Some(greeting[7..].as_ptr()));
+    /// ```
+    pub fn with_config(config: InternerConfig) -> Self {
+        Self {
+            inner: RwLock::new(DataInternerInner::with_config(config)),
+        }
+    }
+
+    /// Returns the configuration this interner was constructed with.
+    pub fn config(&self) -> InternerConfig {
+        self.inner.read().config()
+    }
+
+    /// Returns memory usage statistics for this interner.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, sync::DataInterner};
+    /// let interner = DataInterner::new();
+    /// let greeting = interner.add_str("Hello, world!");
+    /// assert_eq!(interner.find_or_add_str("world"), "world");
+    /// let stats = interner.stats();
+    /// assert_eq!(stats.full_buffers + stats.nonfull_buffers, 1);
+    /// assert_eq!(stats.bytes_used, greeting.len());
+    /// assert_eq!(stats.entries_added, 1);
+    /// assert_eq!(stats.entries_deduplicated, 1);
+    /// ```
+    pub fn stats(&self) -> InternerStats {
+        self.inner.read().stats()
+    }
+
+    /// Returns the number of entries in this interner.
+    ///
+    /// Each call that stored new data (e.g. [`add_str`](Interner::add_str), or [`find_or_add_str`](Interner::find_or_add_str)
+    /// if no existing data was found) adds one entry. Empty values are not stored, so they do not add an entry.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, sync::DataInterner};
+    /// let interner = DataInterner::new();
+    /// assert!(interner.is_empty());
+    /// interner.add_str("Hello, world!");
+    /// interner.find_or_add_str("world");
+    /// interner.add_str("");
+    /// assert_eq!(interner.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.inner.read().len()
+    }
+
+    /// Returns `true` if this interner has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over the data of every entry in this interner, in the order they were added.
+    ///
+    /// Entries added during iteration are also returned.
+    /// Entries added by other threads during iteration may or may not be included.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, sync::DataInterner};
+    /// let interner = DataInterner::new();
+    /// interner.add_str("Hello");
+    /// interner.add_bytes(&[0xFF, 0xFE]);
+    /// interner.add_str("world");
+    /// let entries: Vec<&[u8]> = interner.iter_bytes().collect();
+    /// assert_eq!(entries, [b"Hello" as &[u8], &[0xFF, 0xFE], b"world"]);
+    /// ```
+    pub fn iter_bytes(&self) -> impl Iterator<Item = &[u8]> + '_ {
+        (0..).map_while(move |index| {
+            let this = self.inner.read();
+            // SAFETY: self is borrowed immutably for the '_ lifetime, so no buffer will be invalidated in that lifetime.
+            unsafe { this.entry(index) }
+        })
+    }
+
+    /// Returns an iterator over every entry in this interner that is valid UTF-8, in the order they were added.
+    ///
+    /// See [`iter_bytes`](DataInterner::iter_bytes).
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, sync::DataInterner};
+    /// let interner = DataInterner::new();
+    /// interner.add_str("Hello");
+    /// interner.add_bytes(&[0xFF, 0xFE]);
+    /// interner.add_str("world");
+    /// let entries: Vec<&str> = interner.iter_strs().collect();
+    /// assert_eq!(entries, ["Hello", "world"]);
+    /// ```
+    pub fn iter_strs(&self) -> impl Iterator<Item = &str> + '_ {
+        self.iter_bytes()
+            .filter_map(|entry| std::str::from_utf8(entry).ok())
+    }
+
+    /// Returns how lookups in this interner match interned data.
+    pub fn lookup_mode(&self) -> LookupMode {
+        self.inner.read().lookup_mode()
+    }
+
     /// Convert this to a non-thread-safe interner without deallocating or removing data.
     ///
     /// This function will still invalidate all references, since it takes `self` by value.
@@ -80,6 +232,64 @@ impl DataInterner {
         }
     }
 
+    /// Convert this to a read-only interner that needs no lock for lookups, without deallocating or removing data.
+    ///
+    /// The [`FrozenDataInterner`] is `Send` and `Sync`, supports every `find_*` method, and resolves [`Symbol`]s created by this interner,
+    /// but data can no longer be added to it. This function will still invalidate all references, since it takes `self` by value.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, sync::DataInterner};
+    /// let interner: DataInterner;
+    /// # interner = DataInterner::new();
+    /// // ...
+    /// let greeting = interner.intern_str("Hello, Ferris!");
+    /// let frozen = interner.freeze();
+    /// assert_eq!(frozen.find_str("Ferris"), Some("Ferris"));
+    /// assert_eq!(frozen.resolve(greeting), "Hello, Ferris!");
+    /// ```
+    pub fn freeze(self) -> FrozenDataInterner {
+        FrozenDataInterner::new(self.inner.into_inner())
+    }
+
+    /// Writes a snapshot of this interner's configuration and data to `writer`, which can be read with [`read_snapshot`](DataInterner::read_snapshot).
+    ///
+    /// Every entry with its contents and alignment, all data between entries (for [`LookupMode::CrossEntrySubstring`] lookups), and every [`Symbol`] are preserved.
+    /// The snapshot is written with many small writes, so `writer` should be buffered.
+    ///
+    /// The format is described in [`unsync::DataInterner::write_snapshot`](crate::unsync::DataInterner::write_snapshot),
+    /// and snapshots written by either interner can be read by the other.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, sync::DataInterner};
+    /// let interner = DataInterner::new();
+    /// interner.add_str("Hello, world!");
+    /// let lorem = interner.intern_str("Lorem ipsum");
+    /// let mut snapshot = vec![];
+    /// interner.write_snapshot(&mut snapshot).unwrap();
+    ///
+    /// let restored = DataInterner::read_snapshot(&snapshot[..]).unwrap();
+    /// assert_eq!(restored.len(), 2);
+    /// assert_eq!(restored.find_str("world"), Some("world"));
+    /// assert_eq!(restored.iter_strs().collect::<Vec<_>>(), ["Hello, world!", "Lorem ipsum"]);
+    /// assert_eq!(restored.resolve(lorem), "Lorem ipsum");
+    /// ```
+    pub fn write_snapshot(&self, writer: impl io::Write) -> io::Result<()> {
+        self.inner.read().write_snapshot(writer)
+    }
+
+    /// Reads an interner from a snapshot written by [`write_snapshot`](DataInterner::write_snapshot).
+    ///
+    /// The interner has the configuration it was written with. An error of kind [`InvalidData`](io::ErrorKind::InvalidData) is returned
+    /// if the snapshot is malformed or was written by an unsupported version of this crate.
+    /// Snapshots written by older versions of this crate are supported; those without symbols restore none.
+    pub fn read_snapshot(reader: impl io::Read) -> io::Result<Self> {
+        Ok(Self {
+            inner: RwLock::new(DataInternerInner::read_snapshot(reader)?),
+        })
+    }
+
     /// Clear all data held by this interner without deallocating.
     ///
     /// This function is safe because it takes a &mut self, which guarantees no other references exist into data held by this interner.
@@ -104,6 +314,294 @@ impl DataInterner {
         // SAFETY: We hold a &mut self.
         unsafe { this.clear() }
     }
+
+    /// Return a [`Symbol`] for data equal to `value` in this interner, adding it if it does not yet exist.
+    ///
+    /// Interning equal strings gives equal symbols. The data may be shared with previously-interned data, according to this interner's [`LookupMode`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, sync::DataInterner};
+    /// let interner: DataInterner;
+    /// # interner = DataInterner::new();
+    /// // ...
+    /// let name: &str = "Ferris";
+    /// let greeting1 = interner.intern_str(&format!("Hello, {name}!"));
+    /// let greeting2 = interner.intern_str("Hello, Ferris!");
+    /// assert_eq!(greeting1, greeting2);
+    /// assert_eq!(interner.resolve(greeting1), "Hello, Ferris!");
+    /// ```
+    pub fn intern_str(&self, value: &str) -> Symbol {
+        let value = value.as_bytes();
+        let ty = SymbolType::str();
+        self.find_or_add_with(
+            |this| this.find_symbol(value, ty),
+            // SAFETY: Symbols are only invalidated by clearing the interner, which requires a &mut self.
+            |this| unsafe { this.find_or_add_symbol(value, ty) },
+        )
+    }
+
+    /// Return the [`Symbol`] for data equal to `value` in this interner, if it was interned with [`intern_str`](DataInterner::intern_str).
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, sync::DataInterner};
+    /// let interner: DataInterner;
+    /// # interner = DataInterner::new();
+    /// // ...
+    /// let greeting = interner.intern_str("Hello, Ferris!");
+    /// assert_eq!(interner.find_symbol("Hello, Ferris!"), Some(greeting));
+    /// assert_eq!(interner.find_symbol("Hello"), None);
+    /// ```
+    pub fn find_symbol(&self, value: &str) -> Option<Symbol> {
+        self.inner
+            .read()
+            .find_symbol(value.as_bytes(), SymbolType::str())
+    }
+
+    /// Return the [`Symbol`] for `value` if it lies within this interner's data, e.g. because it was returned by this interner.
+    ///
+    /// Unlike [`find_symbol`](DataInterner::find_symbol), this recovers a symbol from where `value` is stored rather than searching by content,
+    /// so it returns `None` for an equal string stored elsewhere. See [`Interner::owns_str`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, sync::DataInterner};
+    /// let interner = DataInterner::new();
+    /// let greeting = interner.add_str("Hello, Ferris!");
+    /// let name = interner.to_symbol(&greeting[7..13]).unwrap();
+    /// assert_eq!(interner.resolve(name), "Ferris");
+    /// assert_eq!(interner.to_symbol("Ferris"), None);
+    /// assert_eq!(interner.intern_str("Ferris"), name);
+    /// ```
+    pub fn to_symbol(&self, value: &str) -> Option<Symbol> {
+        let mut this = self.inner.write();
+        // SAFETY: value is a &str, so it is initialized, and symbols are only invalidated by clearing the interner, which requires a &mut self.
+        unsafe { this.owned_symbol(value.as_bytes(), SymbolType::str()) }
+    }
+
+    /// Return the string referred to by `symbol`.
+    ///
+    /// Panics if `symbol` was not created by this interner. See [`try_resolve`](DataInterner::try_resolve) for a non-panicking version.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, sync::DataInterner};
+    /// let interner: DataInterner;
+    /// # interner = DataInterner::new();
+    /// // ...
+    /// let greeting = interner.intern_str("Hello, Ferris!");
+    /// assert_eq!(interner.resolve(greeting), "Hello, Ferris!");
+    /// ```
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        self.try_resolve(symbol)
+            .expect("symbol does not belong to this interner")
+    }
+
+    /// Return the string referred to by `symbol`, if it was created by this interner.
+    ///
+    /// Note that a symbol created by a different interner may still refer to a string in this interner.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, sync::DataInterner};
+    /// let interner1: DataInterner;
+    /// let interner2: DataInterner;
+    /// # interner1 = DataInterner::new();
+    /// # interner2 = DataInterner::new();
+    /// // ...
+    /// let greeting = interner1.intern_str("Hello, Ferris!");
+    /// assert_eq!(interner1.try_resolve(greeting), Some("Hello, Ferris!"));
+    /// assert_eq!(interner2.try_resolve(greeting), None);
+    /// ```
+    pub fn try_resolve(&self, symbol: Symbol) -> Option<&str> {
+        let this = self.inner.read();
+        // SAFETY: self is borrowed immutably for the '_ lifetime, so no buffer will be invalidated in that lifetime.
+        let owned = unsafe { this.resolve(symbol, SymbolType::str())? };
+        // SAFETY: Symbols are only created from valid utf8.
+        Some(unsafe { std::str::from_utf8_unchecked(owned) })
+    }
+
+    /// Return a [`TypedSymbol`] for data bytewise-equal to `value` in this interner, adding it if it does not yet exist or is not sufficiently aligned.
+    ///
+    /// Interning bytewise-equal slices of the same type gives equal symbols.
+    ///
+    /// Panics if `T` is a zero-sized type.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, sync::DataInterner};
+    /// let interner: DataInterner;
+    /// # interner = DataInterner::new();
+    /// // ...
+    /// let value1 = interner.intern_slice(&[0x5555u16; 3]);
+    /// let value2 = interner.intern_slice(&[0x5555u16; 3]);
+    /// let value3 = interner.intern_slice(&[0x5555u32; 3]);
+    /// assert_eq!(value1, value2);
+    /// assert_eq!(interner.resolve_slice(value1), &[0x5555u16; 3]);
+    /// assert_eq!(interner.resolve_slice(value3), &[0x5555u32; 3]);
+    /// ```
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytemuck")))]
+    pub fn intern_slice<T: NoUninit + 'static>(&self, value: &[T]) -> TypedSymbol<T> {
+        assert!(
+            size_of::<T>() != 0,
+            "zero-sized types cannot be interned as symbols"
+        );
+        let value: &[u8] = cast_slice(value);
+        let ty = SymbolType::of::<T>();
+        let symbol = self.find_or_add_with(
+            |this| this.find_symbol(value, ty),
+            // SAFETY: Symbols are only invalidated by clearing the interner, which requires a &mut self. value is a [T].
+            |this| unsafe { this.find_or_add_symbol(value, ty) },
+        );
+        TypedSymbol::new(symbol)
+    }
+
+    /// Return a [`TypedSymbol`] for data bytewise-equal to `value` in this interner, adding it if it does not yet exist or is not sufficiently aligned.
+    ///
+    /// This is equivalent to interning a slice containing only `value`.
+    ///
+    /// Panics if `T` is a zero-sized type.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, sync::DataInterner};
+    /// let interner: DataInterner;
+    /// # interner = DataInterner::new();
+    /// // ...
+    /// let value = interner.intern_value(&[1.0f32, 2.0, 3.0]);
+    /// assert_eq!(interner.resolve_value(value), &[1.0, 2.0, 3.0]);
+    /// ```
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytemuck")))]
+    pub fn intern_value<T: NoUninit + 'static>(&self, value: &T) -> TypedSymbol<T> {
+        self.intern_slice(std::slice::from_ref(value))
+    }
+
+    /// Return the [`TypedSymbol`] for data bytewise-equal to `value` in this interner, if it was interned with [`intern_slice`](DataInterner::intern_slice) or [`intern_value`](DataInterner::intern_value).
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, sync::DataInterner};
+    /// let interner: DataInterner;
+    /// # interner = DataInterner::new();
+    /// // ...
+    /// let value = interner.intern_slice(&[1u64, 2, 3]);
+    /// assert_eq!(interner.find_slice_symbol(&[1u64, 2, 3]), Some(value));
+    /// assert_eq!(interner.find_slice_symbol(&[1u64, 2]), None);
+    /// ```
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytemuck")))]
+    pub fn find_slice_symbol<T: NoUninit + 'static>(&self, value: &[T]) -> Option<TypedSymbol<T>> {
+        let value = cast_slice(value);
+        let ty = SymbolType::of::<T>();
+        let this = self.inner.read();
+        let found = this.find_symbol(value, ty);
+        let symbol = match found {
+            Some(symbol) => symbol,
+            None if this.has_unbound_symbols() => {
+                drop(this);
+                // value is a [T], so an unbound symbol for equal data can be bound to T.
+                self.inner.write().bind_symbol(value, ty)?
+            }
+            None => return None,
+        };
+        Some(TypedSymbol::new(symbol))
+    }
+
+    /// Return the slice referred to by `symbol`.
+    ///
+    /// Panics if `symbol` was not created by this interner. See [`try_resolve_slice`](DataInterner::try_resolve_slice) for a non-panicking version.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, sync::DataInterner};
+    /// let interner: DataInterner;
+    /// # interner = DataInterner::new();
+    /// // ...
+    /// let value = interner.intern_slice(&[0xAAAAAAAAu32; 5]);
+    /// assert_eq!(interner.resolve_slice(value), &[0xAAAAAAAAu32; 5]);
+    /// ```
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytemuck")))]
+    pub fn resolve_slice<T: NoUninit + 'static>(&self, symbol: TypedSymbol<T>) -> &[T] {
+        self.try_resolve_slice(symbol)
+            .expect("symbol does not belong to this interner")
+    }
+
+    /// Return the slice referred to by `symbol`, if it was created by this interner.
+    ///
+    /// Note that a symbol created by a different interner may still refer to a slice in this interner.
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytemuck")))]
+    pub fn try_resolve_slice<T: NoUninit + 'static>(&self, symbol: TypedSymbol<T>) -> Option<&[T]> {
+        let this = self.inner.read();
+        // SAFETY: self is borrowed immutably for the '_ lifetime, so no buffer will be invalidated in that lifetime.
+        let owned = unsafe { this.resolve(symbol.symbol(), SymbolType::of::<T>())? };
+        // SAFETY: Symbols of type T are only created from [T] with T's alignment, and T is not zero-sized.
+        Some(unsafe { cast_symbol_data(owned) })
+    }
+
+    /// Return the value referred to by `symbol`.
+    ///
+    /// Panics if `symbol` was not created by this interner, or does not refer to exactly one value.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, sync::DataInterner};
+    /// let interner: DataInterner;
+    /// # interner = DataInterner::new();
+    /// // ...
+    /// let value = interner.intern_value(&0x55555555u32);
+    /// assert_eq!(interner.resolve_value(value), &0x55555555u32);
+    /// ```
+    #[cfg(feature = "bytemuck")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(feature = "bytemuck")))]
+    pub fn resolve_value<T: NoUninit + 'static>(&self, symbol: TypedSymbol<T>) -> &T {
+        match self.resolve_slice(symbol) {
+            [value] => value,
+            _ => panic!("symbol does not refer to exactly one value"),
+        }
+    }
+}
+
+impl DataInterner {
+    /// Returns the result of `find` if it succeeds with a shared lock, otherwise returns the result of `add` with an exclusive lock.
+    ///
+    /// `find` is retried before `add`, in case another thread added the data between the two locks.
+    /// With the `parking_lot` feature enabled, the retry holds an upgradable lock, so it does not block readers.
+    fn find_or_add_with<R>(
+        &self,
+        find: impl Fn(&DataInternerInner) -> Option<R>,
+        add: impl FnOnce(&mut DataInternerInner) -> R,
+    ) -> R {
+        let this = self.inner.read();
+        if let Some(found) = find(&this) {
+            this.record_deduplicated();
+            return found;
+        }
+        drop(this);
+        #[cfg(feature = "parking_lot")]
+        let mut this = {
+            let this = self.inner.upgradable_read();
+            if let Some(found) = find(&this) {
+                this.record_deduplicated();
+                return found;
+            }
+            RwLockUpgradableReadGuard::upgrade(this)
+        };
+        #[cfg(not(feature = "parking_lot"))]
+        let mut this = {
+            let this = self.inner.write();
+            if let Some(found) = find(&this) {
+                this.record_deduplicated();
+                return found;
+            }
+            this
+        };
+        add(&mut this)
+    }
 }
 
 unsafe impl Interner for DataInterner {
@@ -126,7 +624,7 @@ unsafe impl Interner for DataInterner {
     /// let greeting3 = interner.find_str("Hello, Ferris!");
     /// assert_eq!(greeting3, None);
     /// ```
-    fn try_clear(&mut self) -> Result<(), ()> {
+    fn try_clear(&mut self) -> Result<(), InternerError> {
         let this = self.inner.get_mut();
         // SAFETY: We hold a &mut self.
         unsafe { this.clear() }
@@ -160,6 +658,10 @@ unsafe impl Interner for DataInterner {
         unsafe { this.find_bytes(value) }
     }
 
+    fn owns(&self, value: &[u8]) -> bool {
+        self.inner.read().owns(value)
+    }
+
     /// Return a reference to data equal to `value` in this interner, adding it if it does not yet exist.
     ///
     /// Empty slices may not actually be stored.
@@ -179,12 +681,20 @@ unsafe impl Interner for DataInterner {
     /// assert_eq!(greeting3, b"Hello, Sue");
     /// ```
     fn find_or_add_bytes(&self, value: &[u8]) -> &[u8] {
+        expect_added(self.try_find_or_add_bytes(value))
+    }
+
+    /// Like [`find_or_add_bytes`](Interner::find_or_add_bytes), but returns an error instead of panicking if the data could not be added.
+    fn try_find_or_add_bytes(&self, value: &[u8]) -> Result<&[u8], InternerError> {
         if value.is_empty() {
-            return &[];
+            return Ok(&[]);
         }
-        let mut this = self.inner.write();
-        // SAFETY: self is borrowed immutably for the '_ lifetime, so no buffer will be invalidated in that lifetime.
-        unsafe { this.find_or_add_bytes(value) }
+        self.find_or_add_with(
+            // SAFETY: self is borrowed immutably for the '_ lifetime, so no buffer will be invalidated in that lifetime.
+            |this| unsafe { this.find_bytes(value) }.map(Ok),
+            // SAFETY: self is borrowed immutably for the '_ lifetime, so no buffer will be invalidated in that lifetime.
+            |this| unsafe { this.try_add_bytes(value) },
+        )
     }
 
     /// Insert data equal to `value` into this interner, returning a reference to it.
@@ -202,12 +712,17 @@ unsafe impl Interner for DataInterner {
     /// assert_eq!(greeting, b"Hello, Ferris!");
     /// ```
     fn add_bytes(&self, value: &[u8]) -> &[u8] {
+        expect_added(self.try_add_bytes(value))
+    }
+
+    /// Like [`add_bytes`](Interner::add_bytes), but returns an error instead of panicking if the data could not be added.
+    fn try_add_bytes(&self, value: &[u8]) -> Result<&[u8], InternerError> {
         if value.is_empty() {
-            return &[];
+            return Ok(&[]);
         }
         let mut this = self.inner.write();
         // SAFETY: self is borrowed immutably for the '_ lifetime, so no buffer will be invalidated in that lifetime.
-        unsafe { this.add_bytes(value) }
+        unsafe { this.try_add_bytes(value) }
     }
 
     /// Insert `value` into this interner, returning a reference to it's data.
@@ -235,6 +750,41 @@ unsafe impl Interner for DataInterner {
             unsafe { this.add_owned_bytes(value) }
         }
     }
+
+    fn add_owned_bytes_at(&self, value: Vec<u8>, start: usize) -> &[u8] {
+        if value.capacity() == 0 {
+            // Ignore empty buffers
+            debug_assert!(value.is_empty() && start == 0);
+            &[]
+        } else {
+            let mut this = self.inner.write();
+            // SAFETY: self is borrowed immutably for the '_ lifetime, so no buffer will be invalidated in that lifetime.
+            unsafe { this.add_owned_bytes_at(value, start) }
+        }
+    }
+
+    /// Return an [`IStr`] for a string equal to `value` in this interner, adding it if it does not yet exist.
+    ///
+    /// The string is interned as a [`Symbol`], so equal strings always give the same `IStr`, in any [`LookupMode`].
+    fn find_or_add_istr(&self, value: &str) -> IStr<'_> {
+        IStr::new(self.resolve(self.intern_str(value)))
+    }
+
+    /// Insert the string formatted from `args` into this interner, returning a reference to it.
+    ///
+    /// Short output is formatted on the stack, without allocating a temporary `String`.
+    /// This interner is not locked while the formatting implementations used by `args` run, so they may access it (e.g. to resolve a [`Symbol`]).
+    fn add_fmt(&self, args: fmt::Arguments<'_>) -> &str {
+        format_with(args, |value| self.add_str(value))
+    }
+
+    /// Return a reference to a string equal to the one formatted from `args` in this interner, adding it if it does not yet exist.
+    ///
+    /// See [`add_fmt`](Interner::add_fmt) for details.
+    fn find_or_add_fmt(&self, args: fmt::Arguments<'_>) -> &str {
+        format_with(args, |value| self.find_or_add_str(value))
+    }
+
     /// Return a reference to data bytewise-equal to `value` in this interner, if it exists and is sufficiently aligned.
     ///
     /// Empty slices and ZSTs will always succeed and may not actually be stored.
@@ -302,31 +852,44 @@ unsafe impl Interner for DataInterner {
     /// ```
     #[cfg(feature = "bytemuck")]
     fn find_or_add_slice<T: NoUninit + 'static>(&self, value: &[T]) -> &[T] {
+        expect_added(self.try_find_or_add_slice(value))
+    }
+
+    /// Like [`find_or_add_slice`](Interner::find_or_add_slice), but returns an error instead of panicking if the data could not be added.
+    #[cfg(feature = "bytemuck")]
+    fn try_find_or_add_slice<T: NoUninit + 'static>(
+        &self,
+        value: &[T],
+    ) -> Result<&[T], InternerError> {
         if value.is_empty() {
             // Ignore empty slices
-            &[]
+            Ok(&[])
         } else if size_of::<T>() == 0 {
             // Ignore ZSTs
             // SAFETY: T is a ZST
             unsafe {
                 let ptr = NonNull::dangling();
-                std::slice::from_raw_parts(ptr.as_ptr(), value.len())
+                Ok(std::slice::from_raw_parts(ptr.as_ptr(), value.len()))
             }
         } else {
             let len = value.len();
             let value: &[u8] = cast_slice(value);
             let align = std::mem::align_of::<T>();
-            let mut this = self.inner.write();
-            // SAFETY: self is borrowed immutably for the '_ lifetime, so no buffer will be invalidated in that lifetime.
-            // SAFETY: align is a power of two.
-            let owned = unsafe { this.find_or_add_bytes_with_align(value, align) };
+            let owned = self.find_or_add_with(
+                // SAFETY: self is borrowed immutably for the '_ lifetime, so no buffer will be invalidated in that lifetime.
+                // SAFETY: align is a power of two.
+                |this| unsafe { this.find_bytes_with_align(value, align) }.map(Ok),
+                // SAFETY: self is borrowed immutably for the '_ lifetime, so no buffer will be invalidated in that lifetime.
+                // SAFETY: align is a power of two.
+                |this| unsafe { this.try_add_bytes_with_align(value, align) },
+            )?;
             // This would require T: AnyBitPattern, but that is more restrictive than necessary, since we know the bit pattern matches the original
             // cast_slice(owned)
             unsafe {
                 let ptr = owned.as_ptr();
                 let ptr = ptr as *const T;
                 // SAFETY: T is Copy has no interior mutability, and ptr points to equal bytes as value did.
-                std::slice::from_raw_parts(ptr, len)
+                Ok(std::slice::from_raw_parts(ptr, len))
             }
         }
     }
@@ -346,15 +909,21 @@ unsafe impl Interner for DataInterner {
     /// ```
     #[cfg(feature = "bytemuck")]
     fn add_slice<T: NoUninit + 'static>(&self, value: &[T]) -> &[T] {
+        expect_added(self.try_add_slice(value))
+    }
+
+    /// Like [`add_slice`](Interner::add_slice), but returns an error instead of panicking if the data could not be added.
+    #[cfg(feature = "bytemuck")]
+    fn try_add_slice<T: NoUninit + 'static>(&self, value: &[T]) -> Result<&[T], InternerError> {
         if value.is_empty() {
             // Ignore empty slices
-            &[]
+            Ok(&[])
         } else if size_of::<T>() == 0 {
             // Ignore ZSTs
             // SAFETY: T is a ZST
             unsafe {
                 let ptr = NonNull::dangling();
-                std::slice::from_raw_parts(ptr.as_ptr(), value.len())
+                Ok(std::slice::from_raw_parts(ptr.as_ptr(), value.len()))
             }
         } else {
             let len = value.len();
@@ -363,14 +932,14 @@ unsafe impl Interner for DataInterner {
             let mut this = self.inner.write();
             // SAFETY: self is borrowed immutably for the '_ lifetime, so no buffer will be invalidated in that lifetime.
             // SAFETY: align is a power of two.
-            let owned = unsafe { this.add_bytes_with_align(value, align) };
+            let owned = unsafe { this.try_add_bytes_with_align(value, align) }?;
             // This would require T: AnyBitPattern, but that is more restrictive than necessary, since we know the bit pattern matches the original
             // cast_slice(owned)
             unsafe {
                 let ptr = owned.as_ptr();
                 let ptr = ptr as *const T;
                 // SAFETY: T is Copy has no interior mutability, and ptr points to equal bytes as value did.
-                std::slice::from_raw_parts(ptr, len)
+                Ok(std::slice::from_raw_parts(ptr, len))
             }
         }
     }
@@ -393,7 +962,10 @@ unsafe impl Interner for DataInterner {
     /// assert_eq!(value2, Some(&[1u8, 2, 3, 1, 2, 3] as &[u8]));
     /// ```
     #[cfg(feature = "bytemuck")]
-    fn try_add_owned<T: NoUninit + 'static>(&self, value: Vec<T>) -> Result<&[T], Vec<T>> {
+    fn try_add_owned<T: NoUninit + 'static>(
+        &self,
+        value: Vec<T>,
+    ) -> Result<&[T], TryAddOwnedError<T>> {
         if value.capacity() == 0 {
             // Ignore empty buffers
             debug_assert!(value.is_empty());
@@ -407,11 +979,22 @@ unsafe impl Interner for DataInterner {
             }
         } else {
             let len = value.len();
+            if align_of::<T>() != 1 {
+                let error = InternerError::Misaligned {
+                    align: align_of::<T>(),
+                };
+                return Err(TryAddOwnedError { error, value });
+            }
+            let mut this = self.inner.write();
+            if let Err(error) = this.check_budget(value.capacity() * size_of::<T>()) {
+                return Err(TryAddOwnedError { error, value });
+            }
             let value = match try_cast_vec(value) {
                 Ok(value) => value,
-                Err((_, value)) => return Err(value),
+                Err(_) => unreachable!("casting a Vec of 1-aligned elements to bytes cannot fail"),
             };
-            let owned = self.add_owned_bytes(value);
+            // SAFETY: self is borrowed immutably for the '_ lifetime, so no buffer will be invalidated in that lifetime.
+            let owned = unsafe { this.add_owned_bytes(value) };
             // This would require T: AnyBitPattern, but that is more restrictive than necessary, since we know the bit pattern matches the original
             // cast_slice(owned)
             unsafe {
@@ -529,42 +1112,41 @@ unsafe impl Interner for DataInterner {
     }
 }
 
-macro_rules! make_inherent_impls {
-    (impl $ty:ty {
-        $( $(#[cfg($($cfg:tt)*)])? $vis:vis fn $func:ident $([ $($generics:tt)* ])? (&self, value: $valty:ty) -> $retty:ty;)*
-    }) => {
-        impl $ty {
-            $(
-                $(#[cfg($($cfg)*)])?
-                #[doc = concat!("See [`Interner::", stringify!($func), "`]")]
-                $(#[cfg_attr(feature = "doc_cfg", doc(cfg($($cfg)*)))])?
-                $vis fn $func $(< $($generics)* >)? (&self, value: $valty) -> $retty {
-                    <Self as Interner>::$func(self, value)
-                }
-            )*
-        }
-    };
-}
-
 make_inherent_impls! {
     impl DataInterner {
         pub fn find_bytes(&self, value: &[u8]) -> Option<&[u8]>;
+        pub fn owns(&self, value: &[u8]) -> bool;
+        pub fn owns_str(&self, value: &str) -> bool;
         pub fn find_or_add_bytes(&self, value: &[u8]) -> &[u8];
+        pub fn try_find_or_add_bytes(&self, value: &[u8]) -> Result<&[u8], InternerError>;
         pub fn add_bytes(&self, value: &[u8]) -> &[u8];
+        pub fn try_add_bytes(&self, value: &[u8]) -> Result<&[u8], InternerError>;
         pub fn add_owned_bytes(&self, value: Vec<u8>) -> &[u8];
 
         pub fn find_str(&self, value: &str) -> Option<&str>;
         pub fn find_or_add_str(&self, value: &str) -> &str;
+        pub fn try_find_or_add_str(&self, value: &str) -> Result<&str, InternerError>;
         pub fn add_str(&self, value: &str) -> &str;
+        pub fn try_add_str(&self, value: &str) -> Result<&str, InternerError>;
         pub fn add_owned_string(&self, value: String) -> &str;
+        pub fn find_or_add_istr(&self, value: &str) -> IStr<'_>;
+        pub fn add_fmt(&self, value: fmt::Arguments<'_>) -> &str;
+        pub fn find_or_add_fmt(&self, value: fmt::Arguments<'_>) -> &str;
 
         #[cfg(feature = "bytemuck")]
         pub fn find_slice[T: NoUninit + 'static](&self, value: &[T]) -> Option<&[T]>;
         #[cfg(feature = "bytemuck")]
         pub fn find_or_add_slice[T: NoUninit + 'static](&self, value: &[T]) -> &[T];
         #[cfg(feature = "bytemuck")]
+        pub fn try_find_or_add_slice[T: NoUninit + 'static](&self, value: &[T]) -> Result<&[T], InternerError>;
+        #[cfg(feature = "bytemuck")]
         pub fn add_slice[T: NoUninit + 'static](&self, value: &[T]) -> &[T];
         #[cfg(feature = "bytemuck")]
-        pub fn try_add_owned[T: NoUninit + 'static](&self, value: Vec<T>) -> Result<&[T], Vec<T>>;
+        pub fn try_add_slice[T: NoUninit + 'static](&self, value: &[T]) -> Result<&[T], InternerError>;
+        #[cfg(feature = "bytemuck")]
+        pub fn try_add_owned[T: NoUninit + 'static](&self, value: Vec<T>) -> Result<&[T], TryAddOwnedError<T>>;
     }
 }
+
+mod sharded;
+pub use sharded::ShardedDataInterner;
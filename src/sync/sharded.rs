@@ -0,0 +1,503 @@
+use std::{
+    cell::RefCell,
+    collections::hash_map::RandomState,
+    fmt,
+    hash::BuildHasher,
+    num::NonZeroUsize,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use super::{DataInterner, RwLock};
+use crate::{
+    config::{InternerConfig, LookupMode},
+    error::{expect_added, InternerError},
+    inner::DataInternerInner,
+    istr::IStr,
+    stats::InternerStats,
+    unsync::DataInterner as UnSyncDataInterner,
+    util::{make_inherent_impls, Interner},
+};
+
+#[cfg(feature = "bytemuck")]
+use crate::error::TryAddOwnedError;
+#[cfg(feature = "bytemuck")]
+use bytemuck::{cast_slice, NoUninit};
+
+/// A thread-safe data interner that splits its data across multiple [`DataInterner`]s ("shards") by hash.
+///
+/// Each shard has its own lock, so threads adding or finding different data rarely contend with each other.
+///
+/// With [`LookupMode::ExactEntry`], equal data always hashes to the same shard, so lookups and `find_or_add_*` only lock one shard.
+/// With the substring lookup modes, a lookup that misses in the data's own shard also searches every other shard.
+/// Concurrent `find_or_add_*` calls with equal data store it only once, since it is found or added under its shard's lock,
+/// but concurrent calls with overlapping data in different shards (e.g. `"ab"` and `"xaby"`) may store it more than once.
+///
+/// With the `yoke` feature enabled, an `Arc<ShardedDataInterner>` implements [`RcInterner`](crate::RcInterner), like an `Arc<DataInterner>`.
+///
+/// # Example
+/// ```rust
+/// use interner::{Interner, LookupMode, sync::ShardedDataInterner};
+/// let interner = ShardedDataInterner::with_lookup_mode(16, LookupMode::ExactEntry);
+/// std::thread::scope(|s| {
+///     for _ in 0..4 {
+///         s.spawn(|| {
+///             for i in 0..100 {
+///                 interner.find_or_add_str(&format!("token{i}"));
+///             }
+///         });
+///     }
+/// });
+/// let token = interner.find_str("token42").unwrap();
+/// assert_eq!(interner.find_or_add_str("token42").as_ptr(), token.as_ptr());
+/// ```
+pub struct ShardedDataInterner {
+    shards: Box<[DataInterner]>,
+    hasher: RandomState,
+    config: InternerConfig,
+    /// Number of `find_or_add_*` calls that found data in another shard (with the substring lookup modes).
+    deduplicated: AtomicUsize,
+}
+
+impl ShardedDataInterner {
+    /// Constructs a new, empty `ShardedDataInterner` with a number of shards based on the available parallelism.
+    ///
+    /// The interner will not allocate data buffers until something is added to it.
+    pub fn new() -> Self {
+        let parallelism = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        Self::with_shard_count(4 * parallelism)
+    }
+
+    /// Constructs a new, empty `ShardedDataInterner` with `shard_count` shards.
+    ///
+    /// Panics if `shard_count == 0`.
+    ///
+    /// The interner will not allocate data buffers until something is added to it.
+    pub fn with_shard_count(shard_count: usize) -> Self {
+        Self::with_lookup_mode(shard_count, LookupMode::default())
+    }
+
+    /// Constructs a new, empty `ShardedDataInterner` with `shard_count` shards that matches lookups according to `lookup_mode`.
+    ///
+    /// Panics if `shard_count == 0`.
+    ///
+    /// The interner will not allocate data buffers until something is added to it.
+    pub fn with_lookup_mode(shard_count: usize, lookup_mode: LookupMode) -> Self {
+        Self::with_config(shard_count, InternerConfig::new().lookup_mode(lookup_mode))
+    }
+
+    /// Constructs a new, empty `ShardedDataInterner` with `shard_count` shards, each with the given configuration.
+    ///
+    /// If the configuration has a [budget](InternerConfig::budget()), it is split evenly between the shards, and each shard enforces its share separately.
+    /// Data is added to the shard its hash selects, so adding data larger than `budget / shard_count` always fails,
+    /// and adding to a shard that has used its share fails with [`InternerError::BudgetExceeded`] even if other shards still have room.
+    /// Converting to a single interner with [`into_unsync`](ShardedDataInterner::into_unsync) or
+    /// [`into_sync`](ShardedDataInterner::into_sync) restores the full budget.
+    ///
+    /// Panics if `shard_count == 0`.
+    ///
+    /// The interner will not allocate data buffers until something is added to it.
+    pub fn with_config(shard_count: usize, config: InternerConfig) -> Self {
+        assert!(
+            shard_count > 0,
+            "a ShardedDataInterner needs at least one shard"
+        );
+        let shard_config = InternerConfig {
+            budget: config.budget.map(|budget| budget / shard_count),
+            ..config
+        };
+        Self {
+            shards: (0..shard_count)
+                .map(|_| DataInterner::with_config(shard_config))
+                .collect(),
+            hasher: RandomState::new(),
+            config,
+            deduplicated: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the number of shards in this interner.
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Returns how lookups in this interner match interned data.
+    pub fn lookup_mode(&self) -> LookupMode {
+        self.config.lookup_mode
+    }
+
+    /// Returns the configuration this interner was constructed with.
+    ///
+    /// Its budget is the total for all shards. Each shard's budget is an even share of it, see [`with_config`](ShardedDataInterner::with_config).
+    pub fn config(&self) -> InternerConfig {
+        self.config
+    }
+
+    /// Returns memory usage statistics for this interner, summed over all shards.
+    ///
+    /// Each shard is locked in turn, so the result may be inconsistent if other threads are adding data concurrently.
+    pub fn stats(&self) -> InternerStats {
+        let mut stats = InternerStats {
+            entries_deduplicated: self.deduplicated.load(Ordering::Relaxed),
+            ..InternerStats::default()
+        };
+        for shard in &*self.shards {
+            stats += shard.stats();
+        }
+        stats
+    }
+
+    /// Convert this to a non-thread-safe interner without deallocating or removing data.
+    ///
+    /// This function will still invalidate all references, since it takes `self` by value.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, sync::ShardedDataInterner};
+    /// let interner: ShardedDataInterner;
+    /// # interner = ShardedDataInterner::new();
+    /// // ...
+    /// let name: &str = "Ferris";
+    /// let greeting1 = interner.add_str(&format!("Hello, {name}!"));
+    /// let greeting2 = interner.find_str("Hello, Ferris!");
+    /// assert_eq!(greeting1, "Hello, Ferris!");
+    /// assert_eq!(greeting2, Some("Hello, Ferris!"));
+    /// let interner = interner.into_unsync();
+    /// let greeting3 = interner.find_str("Hello, Ferris!");
+    /// assert_eq!(greeting3, Some("Hello, Ferris!"));
+    /// ```
+    pub fn into_unsync(self) -> UnSyncDataInterner {
+        UnSyncDataInterner {
+            inner: RefCell::new(self.into_inner()),
+        }
+    }
+
+    /// Convert this to a single-lock thread-safe interner without deallocating or removing data.
+    ///
+    /// This function will still invalidate all references, since it takes `self` by value.
+    pub fn into_sync(self) -> DataInterner {
+        DataInterner {
+            inner: RwLock::new(self.into_inner()),
+        }
+    }
+
+    fn into_inner(self) -> DataInternerInner {
+        let mut shards = Vec::from(self.shards).into_iter();
+        let mut inner = shards
+            .next()
+            .expect("at least one shard")
+            .inner
+            .into_inner();
+        for shard in shards {
+            inner.append(shard.inner.into_inner());
+        }
+        // Each shard only had its share of the budget, but the merged interner holds all of their data.
+        inner.set_config(self.config);
+        inner
+    }
+
+    /// Clear all data held by this interner without deallocating.
+    ///
+    /// This function is safe because it takes a &mut self, which guarantees no other references exist into data held by this interner.
+    pub fn clear(&mut self) {
+        for shard in &mut *self.shards {
+            shard.clear();
+        }
+        *self.deduplicated.get_mut() = 0;
+    }
+
+    /// Returns `found` after recording that it was found by a `find_or_add_*` call, or `add()` if `found` is `None`.
+    fn found_or_try_add<'a, R: ?Sized>(
+        &'a self,
+        found: Option<&'a R>,
+        add: impl FnOnce() -> Result<&'a R, InternerError>,
+    ) -> Result<&'a R, InternerError> {
+        match found {
+            Some(found) => {
+                self.deduplicated.fetch_add(1, Ordering::Relaxed);
+                Ok(found)
+            }
+            None => add(),
+        }
+    }
+
+    /// Returns the shard that data bytewise-equal to `value` is added to.
+    fn shard(&self, value: &[u8]) -> &DataInterner {
+        let hash = self.hasher.hash_one(value);
+        &self.shards[(hash % self.shards.len() as u64) as usize]
+    }
+
+    /// Finds data in `value`'s shard, then (for substring lookup modes) in the other shards.
+    fn find_with<'a, R: ?Sized>(
+        &'a self,
+        value: &[u8],
+        find: impl Fn(&'a DataInterner) -> Option<&'a R>,
+    ) -> Option<&'a R> {
+        let shard = self.shard(value);
+        if let Some(found) = find(shard) {
+            return Some(found);
+        }
+        match self.lookup_mode() {
+            LookupMode::ExactEntry => None,
+            LookupMode::Substring | LookupMode::CrossEntrySubstring => self
+                .shards
+                .iter()
+                .filter(|other| !std::ptr::eq(*other, shard))
+                .find_map(find),
+        }
+    }
+}
+
+impl Default for ShardedDataInterner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl Interner for ShardedDataInterner {
+    fn try_clear(&mut self) -> Result<(), InternerError> {
+        self.clear();
+        Ok(())
+    }
+
+    fn find_bytes(&self, value: &[u8]) -> Option<&[u8]> {
+        self.find_with(value, |shard| shard.find_bytes(value))
+    }
+
+    fn owns(&self, value: &[u8]) -> bool {
+        self.shards.iter().any(|shard| shard.owns(value))
+    }
+
+    fn find_or_add_bytes(&self, value: &[u8]) -> &[u8] {
+        expect_added(self.try_find_or_add_bytes(value))
+    }
+
+    /// Returns [`InternerError::BudgetExceeded`] if `value`'s shard has used its share of the budget, even if other shards still have room.
+    /// See [`with_config`](ShardedDataInterner::with_config).
+    fn try_find_or_add_bytes(&self, value: &[u8]) -> Result<&[u8], InternerError> {
+        match self.lookup_mode() {
+            LookupMode::ExactEntry => self.shard(value).try_find_or_add_bytes(value),
+            // Finding or adding in value's shard on a miss holds that shard's lock, so equal data added concurrently is stored once.
+            LookupMode::Substring | LookupMode::CrossEntrySubstring => self
+                .found_or_try_add(self.find_bytes(value), || {
+                    self.shard(value).try_find_or_add_bytes(value)
+                }),
+        }
+    }
+
+    fn add_bytes(&self, value: &[u8]) -> &[u8] {
+        self.shard(value).add_bytes(value)
+    }
+
+    /// Returns [`InternerError::BudgetExceeded`] if `value`'s shard has used its share of the budget, even if other shards still have room.
+    /// See [`with_config`](ShardedDataInterner::with_config).
+    fn try_add_bytes(&self, value: &[u8]) -> Result<&[u8], InternerError> {
+        self.shard(value).try_add_bytes(value)
+    }
+
+    fn add_owned_bytes(&self, value: Vec<u8>) -> &[u8] {
+        self.shard(&value).add_owned_bytes(value)
+    }
+
+    fn add_owned_bytes_at(&self, value: Vec<u8>, start: usize) -> &[u8] {
+        self.shard(&value[start..]).add_owned_bytes_at(value, start)
+    }
+
+    /// Equal strings always use the same shard, which interns them as a [`Symbol`](crate::Symbol), so the result is canonical in every [`LookupMode`].
+    fn find_or_add_istr(&self, value: &str) -> IStr<'_> {
+        self.shard(value.as_bytes()).find_or_add_istr(value)
+    }
+
+    #[cfg(feature = "bytemuck")]
+    fn find_slice<T: NoUninit + 'static>(&self, value: &[T]) -> Option<&[T]> {
+        self.find_with(cast_slice(value), |shard| shard.find_slice(value))
+    }
+
+    #[cfg(feature = "bytemuck")]
+    fn find_or_add_slice<T: NoUninit + 'static>(&self, value: &[T]) -> &[T] {
+        expect_added(self.try_find_or_add_slice(value))
+    }
+
+    /// Returns [`InternerError::BudgetExceeded`] if `value`'s shard has used its share of the budget, even if other shards still have room.
+    /// See [`with_config`](ShardedDataInterner::with_config).
+    #[cfg(feature = "bytemuck")]
+    fn try_find_or_add_slice<T: NoUninit + 'static>(
+        &self,
+        value: &[T],
+    ) -> Result<&[T], InternerError> {
+        let shard = self.shard(cast_slice(value));
+        match self.lookup_mode() {
+            LookupMode::ExactEntry => shard.try_find_or_add_slice(value),
+            LookupMode::Substring | LookupMode::CrossEntrySubstring => self
+                .found_or_try_add(self.find_slice(value), || {
+                    shard.try_find_or_add_slice(value)
+                }),
+        }
+    }
+
+    #[cfg(feature = "bytemuck")]
+    fn add_slice<T: NoUninit + 'static>(&self, value: &[T]) -> &[T] {
+        self.shard(cast_slice(value)).add_slice(value)
+    }
+
+    /// Returns [`InternerError::BudgetExceeded`] if `value`'s shard has used its share of the budget, even if other shards still have room.
+    /// See [`with_config`](ShardedDataInterner::with_config).
+    #[cfg(feature = "bytemuck")]
+    fn try_add_slice<T: NoUninit + 'static>(&self, value: &[T]) -> Result<&[T], InternerError> {
+        self.shard(cast_slice(value)).try_add_slice(value)
+    }
+
+    /// Returns [`InternerError::BudgetExceeded`] if `value`'s shard has used its share of the budget, even if other shards still have room.
+    /// See [`with_config`](ShardedDataInterner::with_config).
+    #[cfg(feature = "bytemuck")]
+    fn try_add_owned<T: NoUninit + 'static>(
+        &self,
+        value: Vec<T>,
+    ) -> Result<&[T], TryAddOwnedError<T>> {
+        self.shard(cast_slice(&value)).try_add_owned(value)
+    }
+
+    #[cfg(feature = "bytemuck")]
+    fn find_value<T: NoUninit + 'static>(&self, value: &T) -> Option<&T> {
+        Some(&self.find_slice(std::slice::from_ref(value))?[0])
+    }
+
+    #[cfg(feature = "bytemuck")]
+    fn find_or_add_value<T: NoUninit + 'static>(&self, value: &T) -> &T {
+        &self.find_or_add_slice(std::slice::from_ref(value))[0]
+    }
+
+    #[cfg(feature = "bytemuck")]
+    fn add_value<T: NoUninit + 'static>(&self, value: &T) -> &T {
+        &self.add_slice(std::slice::from_ref(value))[0]
+    }
+}
+
+make_inherent_impls! {
+    impl ShardedDataInterner {
+        pub fn find_bytes(&self, value: &[u8]) -> Option<&[u8]>;
+        pub fn owns(&self, value: &[u8]) -> bool;
+        pub fn owns_str(&self, value: &str) -> bool;
+        pub fn find_or_add_bytes(&self, value: &[u8]) -> &[u8];
+        pub fn try_find_or_add_bytes(&self, value: &[u8]) -> Result<&[u8], InternerError>;
+        pub fn add_bytes(&self, value: &[u8]) -> &[u8];
+        pub fn try_add_bytes(&self, value: &[u8]) -> Result<&[u8], InternerError>;
+        pub fn add_owned_bytes(&self, value: Vec<u8>) -> &[u8];
+
+        pub fn find_str(&self, value: &str) -> Option<&str>;
+        pub fn find_or_add_str(&self, value: &str) -> &str;
+        pub fn try_find_or_add_str(&self, value: &str) -> Result<&str, InternerError>;
+        pub fn add_str(&self, value: &str) -> &str;
+        pub fn try_add_str(&self, value: &str) -> Result<&str, InternerError>;
+        pub fn add_owned_string(&self, value: String) -> &str;
+        pub fn find_or_add_istr(&self, value: &str) -> IStr<'_>;
+        pub fn add_fmt(&self, value: fmt::Arguments<'_>) -> &str;
+        pub fn find_or_add_fmt(&self, value: fmt::Arguments<'_>) -> &str;
+
+        #[cfg(feature = "bytemuck")]
+        pub fn find_slice[T: NoUninit + 'static](&self, value: &[T]) -> Option<&[T]>;
+        #[cfg(feature = "bytemuck")]
+        pub fn find_or_add_slice[T: NoUninit + 'static](&self, value: &[T]) -> &[T];
+        #[cfg(feature = "bytemuck")]
+        pub fn try_find_or_add_slice[T: NoUninit + 'static](&self, value: &[T]) -> Result<&[T], InternerError>;
+        #[cfg(feature = "bytemuck")]
+        pub fn add_slice[T: NoUninit + 'static](&self, value: &[T]) -> &[T];
+        #[cfg(feature = "bytemuck")]
+        pub fn try_add_slice[T: NoUninit + 'static](&self, value: &[T]) -> Result<&[T], InternerError>;
+        #[cfg(feature = "bytemuck")]
+        pub fn try_add_owned[T: NoUninit + 'static](&self, value: Vec<T>) -> Result<&[T], TryAddOwnedError<T>>;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ShardedDataInterner;
+    use crate::{InternerConfig, InternerError, LookupMode};
+
+    #[test]
+    fn substring_lookups_search_all_shards() {
+        let interner = ShardedDataInterner::with_lookup_mode(8, LookupMode::Substring);
+        let words: Vec<&str> = (0..64)
+            .map(|i| interner.add_str(&format!("word[{i}]")))
+            .collect();
+        for (i, word) in words.iter().enumerate() {
+            let found = interner.find_str(&format!("[{i}]")).unwrap();
+            assert_eq!(found.as_ptr(), word[4..].as_ptr());
+        }
+        assert_eq!(interner.find_str("[64]"), None);
+    }
+
+    #[test]
+    fn into_unsync_keeps_data() {
+        let interner = ShardedDataInterner::with_lookup_mode(8, LookupMode::ExactEntry);
+        let pointers: Vec<*const u8> = (0..64)
+            .map(|i| interner.add_str(&format!("word{i}")).as_ptr())
+            .collect();
+        let interner = interner.into_unsync();
+        assert_eq!(interner.lookup_mode(), LookupMode::ExactEntry);
+        for (i, ptr) in pointers.into_iter().enumerate() {
+            assert_eq!(
+                interner.find_str(&format!("word{i}")).unwrap().as_ptr(),
+                ptr
+            );
+        }
+    }
+
+    #[test]
+    fn into_unsync_restores_the_full_budget() {
+        let interner = ShardedDataInterner::with_config(4, InternerConfig::new().budget(4096));
+        interner.add_str("x");
+        assert_eq!(interner.stats().capacity, 1024);
+        let interner = interner.into_unsync();
+        assert_eq!(interner.config().budget, Some(4096));
+        assert_eq!(
+            interner.try_add_str(&"y".repeat(2000)).map(str::len),
+            Ok(2000)
+        );
+        assert!(matches!(
+            interner.try_add_str(&"z".repeat(2000)),
+            Err(InternerError::BudgetExceeded { .. })
+        ));
+    }
+
+    #[test]
+    fn istrs_are_canonical() {
+        // With one shard, the large string is always added to the shard "ab" is in.
+        for shard_count in [1, 4] {
+            let interner = ShardedDataInterner::with_shard_count(shard_count);
+            let a = interner.find_or_add_istr("ab");
+            interner.add_str(&format!("{}ab0", "x".repeat(5000)));
+            let b = interner.find_or_add_istr("ab");
+            assert_eq!(a.as_str().as_ptr(), b.as_str().as_ptr());
+            assert_eq!(a, b);
+        }
+    }
+
+    #[test]
+    fn concurrent_substring_find_or_add_stores_once() {
+        for lookup_mode in [LookupMode::Substring, LookupMode::CrossEntrySubstring] {
+            let interner = ShardedDataInterner::with_lookup_mode(4, lookup_mode);
+            std::thread::scope(|s| {
+                for _ in 0..8 {
+                    s.spawn(|| {
+                        for i in 0..200 {
+                            interner.find_or_add_str(&format!("token{i:03}"));
+                        }
+                    });
+                }
+            });
+            assert_eq!(interner.stats().entries_added, 200);
+        }
+    }
+
+    #[cfg(feature = "yoke")]
+    #[test]
+    fn rc_interner() {
+        use crate::RcInterner;
+        use std::sync::Arc;
+
+        let interner = Arc::new(ShardedDataInterner::with_shard_count(4));
+        let greeting = interner.yoked_find_or_add_str("Hello, Ferris!");
+        drop(interner);
+        assert_eq!(*greeting.get(), "Hello, Ferris!");
+    }
+}
@@ -1,8 +1,11 @@
 use std::{
+    fmt,
     rc::Rc,
-    sync::{RwLock as StdRwLock, RwLockReadGuard, RwLockWriteGuard},
+    sync::{Arc, RwLock as StdRwLock, RwLockReadGuard, RwLockWriteGuard},
 };
 
+use crate::{IStr, InternerError};
+
 #[allow(dead_code)]
 #[derive(Default)]
 #[repr(transparent)]
@@ -48,6 +51,13 @@ pub(crate) unsafe fn is_aligned_to(align: usize, ptr: *const u8) -> bool {
     (ptr as usize).trailing_zeros() >= align.trailing_zeros()
 }
 
+/// Returns the largest power of two that `ptr` is aligned to.
+pub(crate) fn pointer_alignment(ptr: *const u8) -> usize {
+    1usize
+        .checked_shl((ptr as usize).trailing_zeros())
+        .unwrap_or(0)
+}
+
 /// Returns the byte offset required to make `ptr` aligned to `align`.
 ///
 /// SAFETY: align must be a power of two
@@ -57,15 +67,46 @@ pub(crate) unsafe fn align_offset(align: usize, ptr: *const u8) -> usize {
     (align - ((ptr as usize) & mask)) & mask
 }
 
+#[cfg(feature = "bytemuck")]
+use crate::TryAddOwnedError;
 #[cfg(feature = "bytemuck")]
 use bytemuck::NoUninit;
 
+/// A type that stores interned data and hands out references to it.
+///
+/// `Interner` is also implemented for `Rc<I>`, `Arc<I>`, `Box<I>`, and `&I` where `I: Interner`,
+/// so they can be passed directly to generic code. Clearing through an `Rc` or `Arc` only succeeds
+/// if it is the only handle to the interner, and clearing through a `&I` always fails.
+///
+/// # Example
+/// ```rust
+/// use std::sync::Arc;
+/// use interner::{Interner, InternerError, sync::DataInterner};
+/// fn greet<I: Interner>(interner: I, name: &str) -> usize {
+///     interner.add_str(&format!("Hello, {name}!")).len()
+/// }
+/// let mut interner = Arc::new(DataInterner::new());
+/// assert_eq!(greet(&interner, "Ferris"), 14);
+/// assert_eq!(greet(&*interner, "Ferris"), 14);
+/// assert_eq!(greet(interner.clone(), "Ferris"), 14);
+/// let other = interner.clone();
+/// assert_eq!(interner.try_clear(), Err(InternerError::Shared));
+/// drop(other);
+/// assert_eq!(interner.try_clear(), Ok(()));
+/// assert_eq!((&*interner).try_clear(), Err(InternerError::Shared));
+/// ```
+///
+/// # Safety
+///
+/// Implementors must guarantee that any reference returned from a method taking `&self`
+/// remains valid and unchanged for as long as `self` is borrowed, i.e. interned data
+/// must never be moved, deallocated, or modified while such a reference may exist.
 pub unsafe trait Interner {
     /// Attempts to clear all data held by this interner without deallocating.
     ///
     /// This function is safe because it takes a &mut self, which guarantees no other references exist into data held by this interner.
     ///
-    /// If this Interner could not be cleared (e.g. because it is shared, e.g. Rc<dyn Interner>), then an Err variant is returned.
+    /// If this Interner could not be cleared (e.g. because it is shared, e.g. Rc<dyn Interner>), then an error describing why is returned.
     ///
     /// # Example
     /// ```rust
@@ -82,10 +123,12 @@ pub unsafe trait Interner {
     /// let greeting3 = interner.find_str("Hello, Ferris!");
     /// assert_eq!(greeting3, None);
     /// ```
-    fn try_clear(&mut self) -> Result<(), ()>;
+    fn try_clear(&mut self) -> Result<(), InternerError>;
 
     /// Return a reference to data equal to `value` in this interner, if it exists.
     ///
+    /// Whether `value` may match only part of an interned entry depends on the interner's [`LookupMode`](crate::LookupMode).
+    ///
     /// Empty slices will always succeed and may not actually be stored.
     ///
     /// # Example
@@ -104,6 +147,39 @@ pub unsafe trait Interner {
     /// ```
     fn find_bytes(&self, value: &[u8]) -> Option<&[u8]>;
 
+    /// Returns `true` if `value` lies entirely within data held by this interner.
+    ///
+    /// This checks where `value` is stored, not what it contains, so it can be used to skip re-interning data that was
+    /// already returned by this interner. Empty slices are always considered owned.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, unsync::DataInterner};
+    /// let interner = DataInterner::new();
+    /// let greeting = interner.add_bytes(b"Hello, world!");
+    /// assert!(interner.owns(greeting));
+    /// assert!(interner.owns(&greeting[7..12]));
+    /// assert!(!interner.owns(b"Hello, world!"));
+    /// ```
+    fn owns(&self, value: &[u8]) -> bool;
+
+    /// Returns `true` if `value` lies entirely within data held by this interner.
+    ///
+    /// See [`Interner::owns`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, sync::DataInterner};
+    /// let interner = DataInterner::new();
+    /// let name = String::from("Ferris");
+    /// let interned = interner.find_or_add_str(&name);
+    /// assert!(interner.owns_str(interned));
+    /// assert!(!interner.owns_str(&name));
+    /// ```
+    fn owns_str(&self, value: &str) -> bool {
+        self.owns(value.as_bytes())
+    }
+
     /// Return a reference to data equal to `value` in this interner, adding it if it does not yet exist.
     ///
     /// Empty slices may not actually be stored.
@@ -124,6 +200,26 @@ pub unsafe trait Interner {
     /// ```
     fn find_or_add_bytes(&self, value: &[u8]) -> &[u8];
 
+    /// Return a reference to data equal to `value` in this interner, adding it if it does not yet exist,
+    /// or an error if it could not be added.
+    ///
+    /// The default implementation calls [`find_or_add_bytes`](Interner::find_or_add_bytes), so it never returns an error.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, InternerConfig, InternerError, unsync::DataInterner};
+    /// let interner = DataInterner::with_config(InternerConfig::new().budget(16));
+    /// let greeting1 = interner.try_find_or_add_bytes(b"Hello, world!");
+    /// let greeting2 = interner.try_find_or_add_bytes(b"Hello");
+    /// let greeting3 = interner.try_find_or_add_bytes(b"Goodbye, world!");
+    /// assert_eq!(greeting1, Ok(b"Hello, world!" as &[u8]));
+    /// assert_eq!(greeting2, Ok(b"Hello" as &[u8]));
+    /// assert!(matches!(greeting3, Err(InternerError::BudgetExceeded { .. })));
+    /// ```
+    fn try_find_or_add_bytes(&self, value: &[u8]) -> Result<&[u8], InternerError> {
+        Ok(self.find_or_add_bytes(value))
+    }
+
     /// Insert data equal to `value` into this interner, returning a reference to it.
     ///
     /// Empty slices may not actually be stored.
@@ -140,6 +236,23 @@ pub unsafe trait Interner {
     /// ```
     fn add_bytes(&self, value: &[u8]) -> &[u8];
 
+    /// Insert data equal to `value` into this interner, returning a reference to it, or an error if it could not be added.
+    ///
+    /// The default implementation calls [`add_bytes`](Interner::add_bytes), so it never returns an error.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, InternerConfig, InternerError, unsync::DataInterner};
+    /// let interner = DataInterner::with_config(InternerConfig::new().budget(16));
+    /// let greeting1 = interner.try_add_bytes(b"Hello, world!");
+    /// let greeting2 = interner.try_add_bytes(b"Hello, world!");
+    /// assert_eq!(greeting1, Ok(b"Hello, world!" as &[u8]));
+    /// assert!(matches!(greeting2, Err(InternerError::BudgetExceeded { .. })));
+    /// ```
+    fn try_add_bytes(&self, value: &[u8]) -> Result<&[u8], InternerError> {
+        Ok(self.add_bytes(value))
+    }
+
     /// Insert `value` into this interner, returning a reference to it's data.
     ///
     /// This will always succeed if `value.capacity() == 0`. Note that in this case a static slice may be returned.
@@ -156,6 +269,15 @@ pub unsafe trait Interner {
     /// ```
     fn add_owned_bytes(&self, value: Vec<u8>) -> &[u8];
 
+    /// Insert `value` into this interner as an entry starting at byte `start`, returning a reference to the entry.
+    ///
+    /// The bytes before `start` are only padding, and are not found by lookups that match whole entries.
+    /// This is used by the slice builders, which store their elements at an aligned offset into a byte buffer.
+    #[doc(hidden)]
+    fn add_owned_bytes_at(&self, value: Vec<u8>, start: usize) -> &[u8] {
+        &self.add_owned_bytes(value)[start..]
+    }
+
     /// Return a reference to data equal to `value` in this interner, if it exists.
     ///
     /// Empty slices will always succeed and may not actually be stored.
@@ -204,6 +326,26 @@ pub unsafe trait Interner {
         unsafe { std::str::from_utf8_unchecked(owned) }
     }
 
+    /// Return a reference to data equal to `value` in this interner, adding it if it does not yet exist,
+    /// or an error if it could not be added.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, InternerConfig, InternerError, sync::DataInterner};
+    /// let interner = DataInterner::with_config(InternerConfig::new().budget(16));
+    /// let greeting1 = interner.try_find_or_add_str("Hello, world!");
+    /// let greeting2 = interner.try_find_or_add_str("world");
+    /// let greeting3 = interner.try_find_or_add_str("Goodbye, world!");
+    /// assert_eq!(greeting1, Ok("Hello, world!"));
+    /// assert_eq!(greeting2, Ok("world"));
+    /// assert!(matches!(greeting3, Err(InternerError::BudgetExceeded { .. })));
+    /// ```
+    fn try_find_or_add_str(&self, value: &str) -> Result<&str, InternerError> {
+        let owned = self.try_find_or_add_bytes(value.as_bytes())?;
+        // SAFETY: owned == value.as_bytes() bytewise, and value is valid utf8
+        Ok(unsafe { std::str::from_utf8_unchecked(owned) })
+    }
+
     /// Insert data equal to `value` into this interner, returning a reference to it.
     ///
     /// Empty slices may not actually be stored.
@@ -224,6 +366,23 @@ pub unsafe trait Interner {
         unsafe { std::str::from_utf8_unchecked(owned) }
     }
 
+    /// Insert data equal to `value` into this interner, returning a reference to it, or an error if it could not be added.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, InternerConfig, InternerError, sync::DataInterner};
+    /// let interner = DataInterner::with_config(InternerConfig::new().budget(16));
+    /// let greeting1 = interner.try_add_str("Hello, world!");
+    /// let greeting2 = interner.try_add_str("Hello, world!");
+    /// assert_eq!(greeting1, Ok("Hello, world!"));
+    /// assert!(matches!(greeting2, Err(InternerError::BudgetExceeded { .. })));
+    /// ```
+    fn try_add_str(&self, value: &str) -> Result<&str, InternerError> {
+        let owned = self.try_add_bytes(value.as_bytes())?;
+        // SAFETY: owned == value.as_bytes() bytewise, and value is valid utf8
+        Ok(unsafe { std::str::from_utf8_unchecked(owned) })
+    }
+
     /// Insert `value` into this interner, returning a reference to it's data.
     ///
     /// This will always succeed if `value.capacity() == 0`. Note that in this case a static slice may be returned.
@@ -244,6 +403,84 @@ pub unsafe trait Interner {
         unsafe { std::str::from_utf8_unchecked(owned) }
     }
 
+    /// Return an [`IStr`] for a string equal to `value` in this interner, adding it if it does not yet exist.
+    ///
+    /// `IStr`s are compared by identity, so they must be canonical: equal strings must always be at the same address.
+    /// [`sync::DataInterner`](crate::sync::DataInterner) and [`unsync::DataInterner`](crate::unsync::DataInterner)
+    /// ensure this (in any [`LookupMode`](crate::LookupMode)) by interning the string as a [`Symbol`](crate::Symbol).
+    /// The default implementation uses [`find_or_add_str`](Interner::find_or_add_str), so it is only canonical if that
+    /// always returns the same data for equal strings.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, sync::DataInterner};
+    /// let interner: DataInterner;
+    /// # interner = DataInterner::new();
+    /// // ...
+    /// let name: &str = "Ferris";
+    /// let greeting1 = interner.find_or_add_istr(&format!("Hello, {name}!"));
+    /// let greeting2 = interner.find_or_add_istr("Hello, Ferris!");
+    /// assert_eq!(greeting1, greeting2);
+    /// assert_eq!(greeting1.as_str(), "Hello, Ferris!");
+    /// ```
+    fn find_or_add_istr(&self, value: &str) -> IStr<'_> {
+        IStr::new(self.find_or_add_str(value))
+    }
+
+    /// Insert the string formatted from `args` into this interner, returning a reference to it.
+    ///
+    /// [`sync::DataInterner`](crate::sync::DataInterner) and [`unsync::DataInterner`](crate::unsync::DataInterner)
+    /// format short output on the stack, without allocating a temporary `String`.
+    /// The default implementation formats into a `String` and calls [`add_owned_string`](Interner::add_owned_string).
+    /// See also [`format_interned!`](crate::format_interned).
+    ///
+    /// The interners in this crate do not hold a lock or borrow while the formatting implementations used by `args` run,
+    /// so they may access this interner (e.g. to resolve a [`Symbol`](crate::Symbol)).
+    ///
+    /// # Panics
+    ///
+    /// Panics if a formatting implementation returns an error.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, sync::DataInterner};
+    /// let interner: DataInterner;
+    /// # interner = DataInterner::new();
+    /// // ...
+    /// let (prefix, name) = ("std", "fmt");
+    /// let path = interner.add_fmt(format_args!("{prefix}::{name}"));
+    /// assert_eq!(path, "std::fmt");
+    /// ```
+    fn add_fmt(&self, args: fmt::Arguments<'_>) -> &str {
+        match args.as_str() {
+            Some(value) => self.add_str(value),
+            None => self.add_owned_string(fmt::format(args)),
+        }
+    }
+
+    /// Return a reference to a string equal to the one formatted from `args` in this interner, adding it if it does not yet exist.
+    ///
+    /// See [`add_fmt`](Interner::add_fmt) for details.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, unsync::DataInterner};
+    /// let interner: DataInterner;
+    /// # interner = DataInterner::new();
+    /// // ...
+    /// let (prefix, name) = ("std", "fmt");
+    /// let path1 = interner.find_or_add_fmt(format_args!("{prefix}::{name}"));
+    /// let path2 = interner.find_or_add_fmt(format_args!("{prefix}::{name}"));
+    /// assert_eq!(path1, "std::fmt");
+    /// assert!(std::ptr::eq(path1, path2));
+    /// ```
+    fn find_or_add_fmt(&self, args: fmt::Arguments<'_>) -> &str {
+        match args.as_str() {
+            Some(value) => self.find_or_add_str(value),
+            None => self.find_or_add_str(&fmt::format(args)),
+        }
+    }
+
     /// Return a reference to data bytewise-equal to `value` in this interner, if it exists and is sufficiently aligned.
     ///
     /// Empty slices and ZSTs will always succeed and may not actually be stored.
@@ -284,6 +521,30 @@ pub unsafe trait Interner {
     #[cfg(feature = "bytemuck")]
     fn find_or_add_slice<T: NoUninit + 'static>(&self, value: &[T]) -> &[T];
 
+    /// Return a reference to data bytewise-equal to `value` in this interner, adding it if it does not yet exist,
+    /// or an error if it could not be added.
+    ///
+    /// The default implementation calls [`find_or_add_slice`](Interner::find_or_add_slice), so it never returns an error.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, InternerConfig, InternerError, sync::DataInterner};
+    /// let interner = DataInterner::with_config(InternerConfig::new().budget(16));
+    /// let value1 = interner.try_find_or_add_slice(&[0x5555u16; 4]);
+    /// let value2 = interner.try_find_or_add_slice(&[0x5555u16; 2]);
+    /// let value3 = interner.try_find_or_add_slice(&[0xAAAAu16; 8]);
+    /// assert_eq!(value1, Ok(&[0x5555u16; 4] as &[u16]));
+    /// assert_eq!(value2, Ok(&[0x5555u16; 2] as &[u16]));
+    /// assert!(matches!(value3, Err(InternerError::BudgetExceeded { .. })));
+    /// ```
+    #[cfg(feature = "bytemuck")]
+    fn try_find_or_add_slice<T: NoUninit + 'static>(
+        &self,
+        value: &[T],
+    ) -> Result<&[T], InternerError> {
+        Ok(self.find_or_add_slice(value))
+    }
+
     /// Return a reference to data bytewise-equal to `value` in this interner, adding it if it does not yet exist.
     ///
     /// Empty slices and ZSTs may not actually be stored.
@@ -300,15 +561,35 @@ pub unsafe trait Interner {
     #[cfg(feature = "bytemuck")]
     fn add_slice<T: NoUninit + 'static>(&self, value: &[T]) -> &[T];
 
+    /// Insert data bytewise-equal to `value` into this interner, returning a reference to it, or an error if it could not be added.
+    ///
+    /// The default implementation calls [`add_slice`](Interner::add_slice), so it never returns an error.
+    ///
+    /// # Example
+    /// ```rust
+    /// use interner::{Interner, InternerConfig, InternerError, sync::DataInterner};
+    /// let interner = DataInterner::with_config(InternerConfig::new().budget(16));
+    /// let value1 = interner.try_add_slice(&[0x5555u16; 4]);
+    /// let value2 = interner.try_add_slice(&[0x5555u16; 4]);
+    /// assert_eq!(value1, Ok(&[0x5555u16; 4] as &[u16]));
+    /// assert!(matches!(value2, Err(InternerError::BudgetExceeded { .. })));
+    /// ```
+    #[cfg(feature = "bytemuck")]
+    fn try_add_slice<T: NoUninit + 'static>(&self, value: &[T]) -> Result<&[T], InternerError> {
+        Ok(self.add_slice(value))
+    }
+
     /// Insert `value` into this interner, returning a reference to it's data.
     ///
     /// This will always succeed if `size_of::<T>() == 0` or `value.capacity() == 0`. Note that in this case a static slice may be returned.
     ///
-    /// Otherwise, this will fail if `align_of::<T>() != 1`.
+    /// Otherwise, this will fail with [`InternerError::Misaligned`] if `align_of::<T>() != 1`,
+    /// or with [`InternerError::BudgetExceeded`] if taking ownership of `value` would exceed the interner's memory budget.
+    /// The error gives `value` back.
     ///
     /// # Example
     /// ```rust
-    /// use interner::{Interner, sync::DataInterner};
+    /// use interner::{Interner, InternerError, sync::DataInterner};
     /// let interner: DataInterner;
     /// # interner = DataInterner::new();
     /// // ...
@@ -316,9 +597,15 @@ pub unsafe trait Interner {
     /// let value2 = interner.find_bytes(&[1, 2, 3, 1, 2, 3]);
     /// assert_eq!(value1, Ok(&[[1u8, 2, 3]; 8] as &[[u8; 3]]));
     /// assert_eq!(value2, Some(&[1u8, 2, 3, 1, 2, 3] as &[u8]));
+    /// let error = interner.try_add_owned(vec![1u32, 2, 3]).unwrap_err();
+    /// assert_eq!(error.error(), &InternerError::Misaligned { align: 4 });
+    /// assert_eq!(error.into_value(), [1, 2, 3]);
     /// ```
     #[cfg(feature = "bytemuck")]
-    fn try_add_owned<T: NoUninit + 'static>(&self, value: Vec<T>) -> Result<&[T], Vec<T>>;
+    fn try_add_owned<T: NoUninit + 'static>(
+        &self,
+        value: Vec<T>,
+    ) -> Result<&[T], TryAddOwnedError<T>>;
 
     /// Return a reference to data bytewise-equal to `value` in this interner, if it exists and is sufficiently aligned.
     ///
@@ -378,85 +665,291 @@ pub unsafe trait Interner {
     fn add_value<T: NoUninit + 'static>(&self, value: &T) -> &T;
 }
 
-unsafe impl<I: Interner + ?Sized> Interner for Rc<I> {
-    fn try_clear(&mut self) -> Result<(), ()> {
-        if let Some(this) = Rc::get_mut(self) {
-            this.try_clear()
-        } else {
-            Err(())
-        }
-    }
+/// Formats a string directly into an [`Interner`], returning a reference to the interned string.
+///
+/// `format_interned!(interner, ...)` is equivalent to `interner.find_or_add_str(&format!(...))`,
+/// but uses [`Interner::find_or_add_fmt`] to avoid allocating a temporary `String` where possible.
+///
+/// # Example
+/// ```rust
+/// use interner::{format_interned, unsync::DataInterner};
+/// let interner = DataInterner::new();
+/// let (prefix, name) = ("std", "fmt");
+/// let path1 = format_interned!(interner, "{prefix}::{name}");
+/// let path2 = format_interned!(&interner, "{}::{}", prefix, name);
+/// assert_eq!(path1, "std::fmt");
+/// assert!(std::ptr::eq(path1, path2));
+/// ```
+#[macro_export]
+macro_rules! format_interned {
+    ($interner:expr, $($arg:tt)*) => {{
+        use $crate::Interner as _;
+        $interner.find_or_add_fmt(::std::format_args!($($arg)*))
+    }};
+}
 
-    fn find_bytes(&self, value: &[u8]) -> Option<&[u8]> {
-        (**self).find_bytes(value)
-    }
+/// Output of at most this many bytes is formatted on the stack by [`format_with`].
+const FORMAT_STACK_LEN: usize = 256;
 
-    fn find_or_add_bytes(&self, value: &[u8]) -> &[u8] {
-        (**self).find_or_add_bytes(value)
+/// Formats `args` and passes the output to `f`.
+///
+/// This holds no lock, so the formatting implementations used by `args` may access the interner `f` adds the output to.
+/// Output of at most [`FORMAT_STACK_LEN`] bytes is formatted on the stack, and longer output into a `String`.
+pub(crate) fn format_with<R>(args: fmt::Arguments<'_>, f: impl FnOnce(&str) -> R) -> R {
+    struct StackWriter {
+        buffer: [u8; FORMAT_STACK_LEN],
+        len: usize,
+        spilled: Option<String>,
     }
 
-    fn add_bytes(&self, value: &[u8]) -> &[u8] {
-        (**self).add_bytes(value)
+    impl StackWriter {
+        fn as_str(&self) -> &str {
+            match &self.spilled {
+                Some(spilled) => spilled,
+                None => std::str::from_utf8(&self.buffer[..self.len])
+                    .expect("only whole strs are written to the buffer"),
+            }
+        }
     }
 
-    fn add_owned_bytes(&self, value: Vec<u8>) -> &[u8] {
-        (**self).add_owned_bytes(value)
+    impl fmt::Write for StackWriter {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            if let Some(spilled) = &mut self.spilled {
+                spilled.push_str(s);
+            } else if let Some(dest) = self.buffer.get_mut(self.len..self.len + s.len()) {
+                dest.copy_from_slice(s.as_bytes());
+                self.len += s.len();
+            } else {
+                let mut spilled = String::with_capacity(2 * (self.len + s.len()));
+                spilled.push_str(self.as_str());
+                spilled.push_str(s);
+                self.spilled = Some(spilled);
+            }
+            Ok(())
+        }
     }
 
-    #[cfg(feature = "bytemuck")]
-    fn find_slice<T: NoUninit + 'static>(&self, value: &[T]) -> Option<&[T]>
-    where
-        Self: Sized,
-    {
-        (**self).find_slice(value)
+    if let Some(value) = args.as_str() {
+        return f(value);
     }
+    let mut writer = StackWriter {
+        buffer: [0; FORMAT_STACK_LEN],
+        len: 0,
+        spilled: None,
+    };
+    fmt::write(&mut writer, args).expect(
+        "a formatting trait implementation returned an error when the underlying stream did not",
+    );
+    f(writer.as_str())
+}
 
-    #[cfg(feature = "bytemuck")]
-    fn find_or_add_slice<T: NoUninit + 'static>(&self, value: &[T]) -> &[T]
-    where
-        Self: Sized,
-    {
-        (**self).find_or_add_slice(value)
-    }
+/// Implements inherent methods that forward to [`Interner`] methods of the same name, so they can be called without importing the trait.
+macro_rules! make_inherent_impls {
+    (impl $ty:ty {
+        $( $(#[cfg($($cfg:tt)*)])? $vis:vis fn $func:ident $([ $($generics:tt)* ])? (&self, value: $valty:ty) -> $retty:ty;)*
+    }) => {
+        impl $ty {
+            $(
+                $(#[cfg($($cfg)*)])?
+                #[doc = concat!("See [`Interner::", stringify!($func), "`]")]
+                $(#[cfg_attr(feature = "doc_cfg", doc(cfg($($cfg)*)))])?
+                $vis fn $func $(< $($generics)* >)? (&self, value: $valty) -> $retty {
+                    <Self as $crate::Interner>::$func(self, value)
+                }
+            )*
+        }
+    };
+}
+pub(crate) use make_inherent_impls;
 
-    #[cfg(feature = "bytemuck")]
-    fn add_slice<T: NoUninit + 'static>(&self, value: &[T]) -> &[T]
-    where
-        Self: Sized,
-    {
-        (**self).add_slice(value)
-    }
+/// Implements [`Interner`] for a smart pointer or reference to an `Interner` by forwarding every method except `try_clear`,
+/// whose body is given explicitly.
+macro_rules! forward_interner_impl {
+    ($(
+        unsafe impl<$($lt:lifetime,)? I: Interner + ?Sized> Interner for $ty:ty {
+            $($try_clear:tt)*
+        }
+    )*) => {$(
+        unsafe impl<$($lt,)? I: Interner + ?Sized> Interner for $ty {
+            $($try_clear)*
 
-    #[cfg(feature = "bytemuck")]
-    fn try_add_owned<T: NoUninit + 'static>(&self, value: Vec<T>) -> Result<&[T], Vec<T>>
-    where
-        Self: Sized,
-    {
-        (**self).try_add_owned(value)
+            fn find_bytes(&self, value: &[u8]) -> Option<&[u8]> {
+                (**self).find_bytes(value)
+            }
+
+            fn owns(&self, value: &[u8]) -> bool {
+                (**self).owns(value)
+            }
+
+            fn owns_str(&self, value: &str) -> bool {
+                (**self).owns_str(value)
+            }
+
+            fn find_or_add_bytes(&self, value: &[u8]) -> &[u8] {
+                (**self).find_or_add_bytes(value)
+            }
+
+            fn try_find_or_add_bytes(&self, value: &[u8]) -> Result<&[u8], InternerError> {
+                (**self).try_find_or_add_bytes(value)
+            }
+
+            fn add_bytes(&self, value: &[u8]) -> &[u8] {
+                (**self).add_bytes(value)
+            }
+
+            fn try_add_bytes(&self, value: &[u8]) -> Result<&[u8], InternerError> {
+                (**self).try_add_bytes(value)
+            }
+
+            fn add_owned_bytes(&self, value: Vec<u8>) -> &[u8] {
+                (**self).add_owned_bytes(value)
+            }
+
+            fn add_owned_bytes_at(&self, value: Vec<u8>, start: usize) -> &[u8] {
+                (**self).add_owned_bytes_at(value, start)
+            }
+
+            fn find_str(&self, value: &str) -> Option<&str> {
+                (**self).find_str(value)
+            }
+
+            fn find_or_add_str(&self, value: &str) -> &str {
+                (**self).find_or_add_str(value)
+            }
+
+            fn try_find_or_add_str(&self, value: &str) -> Result<&str, InternerError> {
+                (**self).try_find_or_add_str(value)
+            }
+
+            fn add_str(&self, value: &str) -> &str {
+                (**self).add_str(value)
+            }
+
+            fn try_add_str(&self, value: &str) -> Result<&str, InternerError> {
+                (**self).try_add_str(value)
+            }
+
+            fn add_owned_string(&self, value: String) -> &str {
+                (**self).add_owned_string(value)
+            }
+
+            fn find_or_add_istr(&self, value: &str) -> IStr<'_> {
+                (**self).find_or_add_istr(value)
+            }
+
+            fn add_fmt(&self, args: fmt::Arguments<'_>) -> &str {
+                (**self).add_fmt(args)
+            }
+
+            fn find_or_add_fmt(&self, args: fmt::Arguments<'_>) -> &str {
+                (**self).find_or_add_fmt(args)
+            }
+
+            #[cfg(feature = "bytemuck")]
+            fn find_slice<T: NoUninit + 'static>(&self, value: &[T]) -> Option<&[T]>
+            where
+                Self: Sized,
+            {
+                (**self).find_slice(value)
+            }
+
+            #[cfg(feature = "bytemuck")]
+            fn find_or_add_slice<T: NoUninit + 'static>(&self, value: &[T]) -> &[T]
+            where
+                Self: Sized,
+            {
+                (**self).find_or_add_slice(value)
+            }
+
+            #[cfg(feature = "bytemuck")]
+            fn try_find_or_add_slice<T: NoUninit + 'static>(
+                &self,
+                value: &[T],
+            ) -> Result<&[T], InternerError>
+            where
+                Self: Sized,
+            {
+                (**self).try_find_or_add_slice(value)
+            }
+
+            #[cfg(feature = "bytemuck")]
+            fn add_slice<T: NoUninit + 'static>(&self, value: &[T]) -> &[T]
+            where
+                Self: Sized,
+            {
+                (**self).add_slice(value)
+            }
+
+            #[cfg(feature = "bytemuck")]
+            fn try_add_slice<T: NoUninit + 'static>(&self, value: &[T]) -> Result<&[T], InternerError>
+            where
+                Self: Sized,
+            {
+                (**self).try_add_slice(value)
+            }
+
+            #[cfg(feature = "bytemuck")]
+            fn try_add_owned<T: NoUninit + 'static>(
+                &self,
+                value: Vec<T>,
+            ) -> Result<&[T], TryAddOwnedError<T>>
+            where
+                Self: Sized,
+            {
+                (**self).try_add_owned(value)
+            }
+
+            #[cfg(feature = "bytemuck")]
+            fn find_value<T: NoUninit + 'static>(&self, value: &T) -> Option<&T>
+            where
+                Self: Sized,
+            {
+                (**self).find_value(value)
+            }
+
+            #[cfg(feature = "bytemuck")]
+            fn find_or_add_value<T: NoUninit + 'static>(&self, value: &T) -> &T
+            where
+                Self: Sized,
+            {
+                (**self).find_or_add_value(value)
+            }
+
+            #[cfg(feature = "bytemuck")]
+            fn add_value<T: NoUninit + 'static>(&self, value: &T) -> &T
+            where
+                Self: Sized,
+            {
+                (**self).add_value(value)
+            }
+        }
+    )*};
+}
+
+forward_interner_impl! {
+    unsafe impl<I: Interner + ?Sized> Interner for Rc<I> {
+        fn try_clear(&mut self) -> Result<(), InternerError> {
+            Rc::get_mut(self).ok_or(InternerError::Shared)?.try_clear()
+        }
     }
 
-    #[cfg(feature = "bytemuck")]
-    fn find_value<T: NoUninit + 'static>(&self, value: &T) -> Option<&T>
-    where
-        Self: Sized,
-    {
-        (**self).find_value(value)
+    unsafe impl<I: Interner + ?Sized> Interner for Arc<I> {
+        fn try_clear(&mut self) -> Result<(), InternerError> {
+            Arc::get_mut(self).ok_or(InternerError::Shared)?.try_clear()
+        }
     }
 
-    #[cfg(feature = "bytemuck")]
-    fn find_or_add_value<T: NoUninit + 'static>(&self, value: &T) -> &T
-    where
-        Self: Sized,
-    {
-        (**self).find_or_add_value(value)
+    unsafe impl<I: Interner + ?Sized> Interner for Box<I> {
+        fn try_clear(&mut self) -> Result<(), InternerError> {
+            (**self).try_clear()
+        }
     }
 
-    #[cfg(feature = "bytemuck")]
-    fn add_value<T: NoUninit + 'static>(&self, value: &T) -> &T
-    where
-        Self: Sized,
-    {
-        (**self).add_value(value)
+    unsafe impl<'a, I: Interner + ?Sized> Interner for &'a I {
+        /// A shared reference can never be used to clear the interner, so this always returns [`InternerError::Shared`].
+        fn try_clear(&mut self) -> Result<(), InternerError> {
+            Err(InternerError::Shared)
+        }
     }
 }
 
@@ -467,6 +960,11 @@ use std::ops::Deref;
 #[cfg(feature = "yoke")]
 use yoke::Yoke;
 
+/// A cloneable, stably-dereferencing handle to an [`Interner`], e.g. `Rc<unsync::DataInterner>` or `Arc<sync::DataInterner>`.
+///
+/// # Safety
+///
+/// Data interned through the handle must remain valid for as long as any clone of the handle exists.
 #[cfg(feature = "yoke")]
 pub unsafe trait RcInterner: Clone + StableDeref
 where
@@ -618,6 +1116,15 @@ where
         Yoke::attach_to_cart(self.clone(), |this| this.add_slice(value))
     }
 
+    /// Return a [`YokedSliceBuilder`](crate::builder::YokedSliceBuilder) that holds a clone of this handle,
+    /// and finalizes into a slice in this interner.
+    #[cfg(all(feature = "builder", feature = "bytemuck"))]
+    fn yoked_slice_builder<T: NoUninit + 'static>(
+        &self,
+    ) -> crate::builder::YokedSliceBuilder<T, Self> {
+        crate::builder::YokedSliceBuilder::new(self.clone())
+    }
+
     /// Return a reference to data bytewise-equal to `value` in this interner, adding it if it does exists or is not sufficiently aligned.
     ///
     /// This will always succeed if `size_of::<T>() == 0` or `value.capacity() == 0`. Note that in this case a static slice may be returned.
@@ -627,7 +1134,7 @@ where
     fn yoked_try_add_owned<T: NoUninit + 'static>(
         &self,
         value: Vec<T>,
-    ) -> Result<Yoke<&'static [T], Self>, Vec<T>> {
+    ) -> Result<Yoke<&'static [T], Self>, TryAddOwnedError<T>> {
         Yoke::try_attach_to_cart(self.clone(), move |this| this.try_add_owned(value))
     }
 
@@ -659,3 +1166,63 @@ where
 /// Covers Rc<unsync::DataInterner> and Arc<sync::DataInterner>, for example.
 #[cfg(feature = "yoke")]
 unsafe impl<T: Clone + StableDeref> RcInterner for T where <Self as Deref>::Target: Interner {}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt;
+
+    use super::{format_with, FORMAT_STACK_LEN};
+    use crate::{sync, unsync, Symbol};
+
+    /// Resolves a symbol while being formatted, like a user's `Display` impl might.
+    struct Resolving<'a, I>(&'a I, Symbol);
+
+    impl fmt::Display for Resolving<'_, unsync::DataInterner> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.0.resolve(self.1))
+        }
+    }
+
+    impl fmt::Display for Resolving<'_, sync::DataInterner> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.0.resolve(self.1))
+        }
+    }
+
+    #[test]
+    fn format_with_spills_long_output() {
+        let short = "x".repeat(FORMAT_STACK_LEN);
+        format_with(format_args!("{}", short), |value| assert_eq!(value, short));
+        let long = format!("{short}{}", 42);
+        format_with(format_args!("{}{}", short, 42), |value| {
+            assert_eq!(value, long)
+        });
+        format_with(format_args!("static"), |value| assert_eq!(value, "static"));
+    }
+
+    #[test]
+    fn unsync_formatting_may_access_the_interner() {
+        let interner = unsync::DataInterner::new();
+        let name = interner.intern_str("name");
+        let added = interner.add_fmt(format_args!("{}!", Resolving(&interner, name)));
+        assert_eq!(added, "name!");
+        let found = interner.find_or_add_fmt(format_args!("{}!", Resolving(&interner, name)));
+        assert_eq!(found.as_ptr(), added.as_ptr());
+        let long = "y".repeat(2 * FORMAT_STACK_LEN);
+        let added = interner.add_fmt(format_args!("{}{}", long, Resolving(&interner, name)));
+        assert_eq!(added, format!("{long}name"));
+    }
+
+    #[test]
+    fn sync_formatting_may_access_the_interner() {
+        let interner = sync::DataInterner::new();
+        let name = interner.intern_str("name");
+        let added = interner.add_fmt(format_args!("{}!", Resolving(&interner, name)));
+        assert_eq!(added, "name!");
+        let found = interner.find_or_add_fmt(format_args!("{}!", Resolving(&interner, name)));
+        assert_eq!(found.as_ptr(), added.as_ptr());
+        let long = "y".repeat(2 * FORMAT_STACK_LEN);
+        let added = interner.add_fmt(format_args!("{}{}", long, Resolving(&interner, name)));
+        assert_eq!(added, format!("{long}name"));
+    }
+}